@@ -0,0 +1,236 @@
+//! Criterion benches for every day's generator and parts.
+//!
+//! ```text
+//! cargo bench --bench days                          # everything
+//! cargo bench --bench days -- day16                 # a single day
+//! cargo bench --bench days -- --save-baseline main  # then --baseline main to compare
+//! ```
+//!
+//! Each day runs on its puzzle sample and, when `input/2022/dayN.txt` is checked
+//! out, on the personal input. After the run a markdown summary of the mean times
+//! (and the change against the previous run, when there is one) is printed and
+//! written to `target/criterion/summary.md`.
+
+use std::{
+    env, fs,
+    hint::black_box,
+    path::{Path, PathBuf},
+    str,
+    time::{Duration, SystemTime},
+};
+
+use advent_of_code_2022::{
+    solver::{Answer, Day, Solver},
+    *,
+};
+use criterion::{BenchmarkGroup, BenchmarkId, Criterion, measurement::WallTime};
+
+const STAGES: [&str; 3] = ["generator", "part1", "part2"];
+const INPUTS: [&str; 2] = ["sample", "input"];
+
+/// A benched part. On the sample it's usually plain `S::part1`/`S::part2`.
+type Part<S> = fn(&<S as Solver>::Parsed) -> Answer;
+
+fn bench_day<S: Solver>(
+    c: &mut Criterion,
+    day: u8,
+    sample: &str,
+    sample_parts: &[(&str, Part<S>)],
+) {
+    let mut group = c.benchmark_group(format!("day{day:02}"));
+
+    let run = |group: &mut BenchmarkGroup<WallTime>,
+               name: &str,
+               input: &str,
+               parts: &[(&str, Part<S>)]| {
+        group.bench_with_input(BenchmarkId::new("generator", name), input, |b, input| {
+            b.iter(|| S::parse(black_box(input)))
+        });
+
+        let parsed = S::parse(input);
+        for &(stage, part) in parts {
+            group.bench_with_input(BenchmarkId::new(stage, name), &parsed, |b, parsed| {
+                b.iter(|| part(black_box(parsed)))
+            });
+        }
+    };
+
+    run(
+        &mut group,
+        "sample",
+        sample.trim_end_matches('\n'),
+        sample_parts,
+    );
+
+    if let Ok(input) = input::load(day)
+        && !input.is_empty()
+    {
+        let mut parts: Vec<(&str, Part<S>)> = vec![("part1", S::part1)];
+        if S::HAS_PART2 {
+            parts.push(("part2", |parsed| S::part2(parsed).unwrap()));
+        }
+        run(&mut group, "input", &input, &parts);
+    }
+    group.finish();
+}
+
+/// One `(stage, fn)` pair for [`bench_day`]'s sample parts.
+macro_rules! sample_part {
+    ($s:ty, part1) => {
+        ("part1", <$s as Solver>::part1 as Part<$s>)
+    };
+    ($s:ty, part2) => {
+        (
+            "part2",
+            (|parsed| <$s as Solver>::part2(parsed).unwrap()) as Part<$s>,
+        )
+    };
+    ($s:ty, $stage:ident, $f:expr) => {
+        (stringify!($stage), $f as Part<$s>)
+    };
+}
+
+/// Run a `partN_with` with the given `PuzzleParams`.
+macro_rules! with_params {
+    ($f:path, $params:expr) => {
+        |parsed: &_| Answer::from($f(parsed, &$params))
+    };
+}
+
+macro_rules! days {
+    ($($num:literal => $sample:expr, [$($stage:ident $(= $f:expr)?),*];)*) => {
+        fn benches(c: &mut Criterion) {
+            $(bench_day::<Day<$num>>(
+                c,
+                $num,
+                $sample,
+                &[$(sample_part!(Day<$num>, $stage $(, $f)?)),*],
+            );)*
+        }
+    };
+}
+
+// The part list says which parts run on the sample, `partN = f` runs `f`
+// instead for days whose sample needs other constants
+days! {
+    1 => day01::SAMPLE, [part1, part2];
+    2 => str::from_utf8(day02::SAMPLE).unwrap(), [part1, part2];
+    3 => day03::SAMPLE, [part1, part2];
+    4 => day04::SAMPLE, [part1, part2];
+    5 => day05::SAMPLE, [part1, part2];
+    6 => str::from_utf8(day06::SAMPLE[0]).unwrap(), [part1, part2];
+    7 => day07::SAMPLE, [part1, part2];
+    8 => day08::SAMPLE, [part1, part2];
+    9 => day09::SAMPLE, [part1, part2];
+    // the sample screen has no letters to read
+    10 => day10::SAMPLE, [part1];
+    11 => day11::SAMPLE, [part1, part2];
+    12 => day12::SAMPLE, [part1, part2];
+    13 => day13::SAMPLE, [part1, part2];
+    14 => day14::SAMPLE, [part1, part2];
+    15 => day15::SAMPLE, [
+        part1 = with_params!(day15::part1_with, day15::PuzzleParams::SAMPLE),
+        part2 = with_params!(day15::part2_with, day15::PuzzleParams::SAMPLE)
+    ];
+    16 => day16::SAMPLE, [part1, part2];
+    17 => day17::SAMPLE, [part1, part2];
+    18 => day18::SAMPLE, [part1, part2];
+    19 => day19::SAMPLE, [
+        part1 = with_params!(day19::part1_with, day19::PuzzleParams::SAMPLE),
+        part2 = with_params!(day19::part2_with, day19::PuzzleParams::SAMPLE)
+    ];
+    20 => day20::SAMPLE, [part1, part2];
+    21 => day21::SAMPLE, [part1, part2];
+    22 => day22::SAMPLE, [
+        part1,
+        part2 = with_params!(day22::part2_with, day22::PuzzleParams::SAMPLE)
+    ];
+    23 => day23::SAMPLE, [part1, part2];
+    24 => day24::SAMPLE, [part1, part2];
+    25 => day25::SAMPLE, [part1];
+    // Insert day before
+}
+
+fn criterion_dir() -> PathBuf {
+    // Same lookup as criterion itself
+    if let Some(home) = env::var_os("CRITERION_HOME") {
+        return PathBuf::from(home);
+    }
+    env::var_os("CARGO_TARGET_DIR")
+        .map_or_else(
+            || Path::new(env!("CARGO_MANIFEST_DIR")).join("target"),
+            PathBuf::from,
+        )
+        .join("criterion")
+}
+
+/// Mean point estimate from an `estimates.json` written during this run.
+fn mean(path: &Path, since: SystemTime) -> Option<f64> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    if modified < since {
+        return None;
+    }
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    json["mean"]["point_estimate"].as_f64()
+}
+
+fn format_ns(ns: f64) -> String {
+    match ns {
+        ns if ns < 1e3 => format!("{ns:.2} ns"),
+        ns if ns < 1e6 => format!("{:.2} µs", ns / 1e3),
+        ns if ns < 1e9 => format!("{:.2} ms", ns / 1e6),
+        ns => format!("{:.2} s", ns / 1e9),
+    }
+}
+
+fn cell(dir: &Path, since: SystemTime) -> Option<String> {
+    let time = format_ns(mean(&dir.join("new/estimates.json"), since)?);
+    Some(match mean(&dir.join("change/estimates.json"), since) {
+        Some(change) => format!("{time} ({:+.1}%)", change * 100.0),
+        None => time,
+    })
+}
+
+fn summary(since: SystemTime) -> String {
+    let dir = criterion_dir();
+    let mut res = String::from("| day | input | generator | part1 | part2 |\n");
+    res.push_str("|---|---|---|---|---|\n");
+
+    for day in solver::registry() {
+        for input in INPUTS {
+            let cells = STAGES.map(|stage| {
+                cell(
+                    &dir.join(format!("day{:02}/{stage}/{input}", day.day)),
+                    since,
+                )
+            });
+            if cells.iter().all(Option::is_none) {
+                continue;
+            }
+            let [generator, part1, part2] = cells.map(|c| c.unwrap_or_else(|| "-".to_string()));
+            res.push_str(&format!(
+                "| {} | {input} | {generator} | {part1} | {part2} |\n",
+                day.day
+            ));
+        }
+    }
+
+    res
+}
+
+fn main() {
+    let start = SystemTime::now();
+    let mut c = Criterion::default()
+        .warm_up_time(Duration::from_secs(1))
+        .measurement_time(Duration::from_secs(3))
+        .configure_from_args();
+
+    benches(&mut c);
+    c.final_summary();
+
+    let summary = summary(start);
+    if summary.lines().count() > 2 {
+        println!("\n{summary}");
+        let _ = fs::write(criterion_dir().join("summary.md"), summary);
+    }
+}
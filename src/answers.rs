@@ -0,0 +1,195 @@
+use std::{collections::BTreeMap, env, fmt, fs, io, path::PathBuf, str::FromStr};
+
+use crate::{
+    input,
+    solver::{self, Answer},
+};
+
+/// Accepted answers for one day; a missing part is not checked.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DayAnswers {
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+}
+
+/// The answers manifest, `answers/2022.toml` unless `AOC_ANSWERS` points elsewhere.
+///
+/// ```toml
+/// [day1]
+/// part1 = 74198
+/// part2 = 209914
+///
+/// [day5]
+/// part1 = "HNSNMTLHQ"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    days: BTreeMap<u8, DayAnswers>,
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io(e) => write!(f, "{e}"),
+            ManifestError::Toml(e) => write!(f, "{e}"),
+            ManifestError::Invalid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+fn parse_answer(day: u8, part: &str, value: &toml::Value) -> Result<Answer, ManifestError> {
+    match value {
+        toml::Value::Integer(n) => Ok(Answer::Int((*n).into())),
+        toml::Value::String(s) => Ok(Answer::Str(s.clone())),
+        _ => Err(ManifestError::Invalid(format!(
+            "day{day}.{part} must be an integer or a string"
+        ))),
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = ManifestError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let table = s.parse::<toml::Table>().map_err(ManifestError::Toml)?;
+        let mut days = BTreeMap::new();
+
+        for (key, value) in &table {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|n| n.parse().ok())
+                .filter(|n| (1..=25).contains(n))
+                .ok_or_else(|| ManifestError::Invalid(format!("unknown section [{key}]")))?;
+            let parts = value
+                .as_table()
+                .ok_or_else(|| ManifestError::Invalid(format!("{key} must be a table")))?;
+
+            let mut answers = DayAnswers::default();
+            for (part, value) in parts {
+                let slot = match part.as_str() {
+                    "part1" => &mut answers.part1,
+                    "part2" => &mut answers.part2,
+                    _ => {
+                        return Err(ManifestError::Invalid(format!("unknown key {key}.{part}")));
+                    }
+                };
+                *slot = Some(parse_answer(day, part, value)?);
+            }
+            days.insert(day, answers);
+        }
+
+        Ok(Self { days })
+    }
+}
+
+impl Manifest {
+    pub fn path() -> PathBuf {
+        env::var_os("AOC_ANSWERS").map_or_else(
+            || PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("answers/2022.toml"),
+            PathBuf::from,
+        )
+    }
+
+    pub fn load() -> Result<Self, ManifestError> {
+        fs::read_to_string(Self::path())
+            .map_err(ManifestError::Io)?
+            .parse()
+    }
+
+    pub fn get(&self, day: u8) -> Option<&DayAnswers> {
+        self.days.get(&day)
+    }
+}
+
+/// Shared regression test: solve the day's personal input and compare with the
+/// manifest. Skips (see [`input::skip`]) when the input, the manifest or the
+/// day's entry is missing, but panics on a malformed manifest.
+pub fn check(day: u8) {
+    let Some(input) = input::load_or_skip(day) else {
+        return;
+    };
+
+    let manifest = match Manifest::load() {
+        Ok(manifest) => manifest,
+        Err(ManifestError::Io(e)) if e.kind() == io::ErrorKind::NotFound => {
+            input::skip(
+                day,
+                format_args!("{} not found", Manifest::path().display()),
+            );
+            return;
+        }
+        Err(e) => panic!("{}: {e}", Manifest::path().display()),
+    };
+
+    let Some(expected) = manifest
+        .get(day)
+        .filter(|a| a.part1.is_some() || a.part2.is_some())
+    else {
+        input::skip(day, format_args!("no [day{day}] answers"));
+        return;
+    };
+
+    let (part1, part2) = solver::get(day).expect("unknown day").solve(&input);
+
+    if let Some(answer) = &expected.part1 {
+        assert_eq!(&part1, answer, "day {day} part 1");
+    }
+    if let Some(answer) = &expected.part2 {
+        assert_eq!(part2.as_ref(), Some(answer), "day {day} part 2");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn parse_test() {
+        let manifest: Manifest = "[day1]\npart1 = 1\n\n[day5]\npart2 = \"CMZ\"\n"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            manifest.get(1),
+            Some(&DayAnswers {
+                part1: Some(Answer::Int(1)),
+                part2: None
+            })
+        );
+        assert_eq!(manifest.get(5).unwrap().part2, Some(Answer::from("CMZ")));
+        assert_eq!(manifest.get(2), None);
+    }
+
+    #[test]
+    pub fn invalid_test() {
+        for s in [
+            "[day26]\npart1 = 1",
+            "[day1]\npart3 = 1",
+            "[day1]\npart1 = 1.5",
+        ] {
+            assert!(matches!(
+                s.parse::<Manifest>(),
+                Err(ManifestError::Invalid(_))
+            ));
+        }
+    }
+
+    #[test]
+    pub fn repo_manifest_test() {
+        let manifest: Manifest =
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/answers/2022.toml"))
+                .parse()
+                .unwrap();
+
+        assert!((1..=25).all(|day| manifest.get(day).is_some()));
+    }
+}
@@ -0,0 +1,108 @@
+//! Run a single day without `cargo aoc`.
+//!
+//! ```text
+//! cargo run --release --bin aoc -- --day 1 [--part 2] [--input input/2022/day1.txt]
+//! ```
+//!
+//! The input is read from stdin when `--input` is missing or `-`.
+
+use std::{
+    env,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    process,
+    time::Duration,
+};
+
+use advent_of_code_2022::solver;
+
+const USAGE: &str = "usage: aoc --day N [--part P] [--input PATH]";
+
+#[derive(Debug, Default)]
+struct Args {
+    day: u8,
+    part: Option<u8>,
+    input: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    fn number(flag: &str, value: Option<String>) -> Result<u8, String> {
+        let value = value.ok_or_else(|| format!("{flag} needs a value"))?;
+        value
+            .parse()
+            .map_err(|_| format!("{flag} expects a number, got {value:?}"))
+    }
+
+    let mut res = Args::default();
+    let mut day = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" | "-d" => day = Some(number("--day", args.next())?),
+            "--part" | "-p" => res.part = Some(number("--part", args.next())?),
+            "--input" | "-i" => {
+                res.input = Some(args.next().ok_or("--input needs a value")?);
+            }
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ => return Err(format!("unknown argument {arg:?}\n{USAGE}")),
+        }
+    }
+
+    res.day = day.ok_or_else(|| format!("missing --day\n{USAGE}"))?;
+    if let Some(part) = res.part
+        && !(1..=2).contains(&part)
+    {
+        return Err(format!("--part must be 1 or 2, got {part}"));
+    }
+
+    Ok(res)
+}
+
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    let mut input = match path {
+        None | Some("-") => {
+            let mut buf = String::new();
+            io::stdin().read_to_string(&mut buf)?;
+            buf
+        }
+        Some(path) => fs::read_to_string(path)?,
+    };
+
+    // Same as the regression tests: strip the trailing newline(s)
+    input.truncate(input.trim_end_matches('\n').len());
+    Ok(input)
+}
+
+fn report(day: u8, part: u8, answer: impl Display, generator: Duration, runner: Duration) {
+    println!("Day {day} - Part {part} : {answer}");
+    println!("\tgenerator: {generator:?},");
+    println!("\trunner: {runner:?}\n");
+}
+
+fn dispatch(args: &Args, input: &str) -> Result<(), String> {
+    let entry = solver::get(args.day).ok_or("not implemented")?;
+    let run = entry.run(input, args.part)?;
+
+    for (part, answer, runner) in run.parts {
+        report(args.day, part, answer, run.generator, runner);
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = parse_args(env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(2);
+    });
+
+    let input = read_input(args.input.as_deref()).unwrap_or_else(|e| {
+        eprintln!("failed to read input: {e}");
+        process::exit(1);
+    });
+
+    if let Err(e) = dispatch(&args, &input) {
+        eprintln!("day {}: {e}", args.day);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,221 @@
+//! Create a new day from `src/template.rs`.
+//!
+//! ```text
+//! cargo run --bin scaffold -- 7
+//! ```
+//!
+//! Writes `src/day07.rs` and registers it at the `// Insert ... before` markers:
+//! `pub mod day07;` in `src/lib.rs`, its `Solver` impl and registry entry in
+//! `src/solver.rs`, and its benchmark in `benches/days.rs`. Also stubs out
+//! `input/2022/day7.txt` and `[day7]` in the answers manifest. An existing day
+//! is never overwritten.
+
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process,
+};
+
+/// Where each new line goes: file, marker, and the line for `day`.
+type Registration = (&'static str, &'static str, fn(u8) -> String);
+
+const REGISTRATIONS: [Registration; 4] = [
+    ("src/lib.rs", "// Insert before", |day| {
+        format!("pub mod day{day:02};")
+    }),
+    ("src/solver.rs", "    // Insert solver before", |day| {
+        format!("    {day} => day{day:02} str day{day:02}::Object, [part1, part2];")
+    }),
+    ("src/solver.rs", "    // Insert entry before", |day| {
+        format!("    Entry::new::<Day<{day}>>({day}, \"Day {day}\"),")
+    }),
+    ("benches/days.rs", "    // Insert day before", |day| {
+        format!("    {day} => day{day:02}::SAMPLE, [part1, part2];")
+    }),
+];
+
+fn render(template: &str, day: u8) -> String {
+    template
+        .replace("dayN", &format!("day{day}"))
+        .replace("check(N)", &format!("check({day})"))
+}
+
+/// Add `line` to `text` (the contents of `file`) right before `marker`.
+fn insert_line(text: &str, file: &str, marker: &str, line: &str) -> Result<String, String> {
+    if text.lines().any(|l| l.trim() == line.trim()) {
+        return Err(format!("`{}` is already in {file}", line.trim()));
+    }
+
+    let pos = text
+        .find(marker)
+        .ok_or_else(|| format!("no `{}` marker in {file}", marker.trim()))?;
+    Ok(format!("{}{line}\n{}", &text[..pos], &text[pos..]))
+}
+
+fn answers_stub(day: u8) -> String {
+    format!("\n[day{day}]\n# part1 = 0\n# part2 = 0\n")
+}
+
+fn scaffold(root: &Path, day: u8) -> Result<Vec<String>, String> {
+    let err = |path: &Path, e: io::Error| format!("{}: {e}", path.display());
+    let mut log = Vec::new();
+
+    let template_path = root.join("src/template.rs");
+    let day_path = root.join(format!("src/day{day:02}.rs"));
+    let input_path = root.join(format!("input/2022/day{day}.txt"));
+    let answers_path = root.join("answers/2022.toml");
+
+    let template = fs::read_to_string(&template_path).map_err(|e| err(&template_path, e))?;
+
+    // Work out every edit before writing anything, so a day that is already
+    // registered somewhere leaves the tree untouched
+    let mut edits: Vec<(&str, String)> = Vec::new();
+    for (file, marker, line) in REGISTRATIONS {
+        let text = match edits.iter().position(|&(f, _)| f == file) {
+            Some(i) => edits.remove(i).1,
+            None => {
+                let path = root.join(file);
+                fs::read_to_string(&path).map_err(|e| err(&path, e))?
+            }
+        };
+        edits.push((file, insert_line(&text, file, marker, &line(day))?));
+    }
+
+    // create_new refuses to clobber a day that already exists
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&day_path)
+        .and_then(|mut f| f.write_all(render(&template, day).as_bytes()))
+        .map_err(|e| err(&day_path, e))?;
+    log.push(format!("created {}", day_path.display()));
+
+    for (file, text) in edits {
+        let path = root.join(file);
+        fs::write(&path, text).map_err(|e| err(&path, e))?;
+        log.push(format!("added day{day:02} to {}", path.display()));
+    }
+
+    if input_path.exists() {
+        log.push(format!("kept existing {}", input_path.display()));
+    } else {
+        if let Some(dir) = input_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| err(dir, e))?;
+        }
+        fs::write(&input_path, "").map_err(|e| err(&input_path, e))?;
+        log.push(format!("created empty {}", input_path.display()));
+    }
+
+    let answers = fs::read_to_string(&answers_path).unwrap_or_default();
+    if answers.lines().any(|l| l.trim() == format!("[day{day}]")) {
+        log.push(format!(
+            "kept existing [day{day}] in {}",
+            answers_path.display()
+        ));
+    } else {
+        if let Some(dir) = answers_path.parent() {
+            fs::create_dir_all(dir).map_err(|e| err(dir, e))?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&answers_path)
+            .and_then(|mut f| f.write_all(answers_stub(day).as_bytes()))
+            .map_err(|e| err(&answers_path, e))?;
+        log.push(format!("added [day{day}] to {}", answers_path.display()));
+    }
+
+    Ok(log)
+}
+
+fn main() {
+    let day = match env::args().nth(1).map(|arg| arg.parse::<u8>()) {
+        Some(Ok(day @ 1..=25)) => day,
+        _ => {
+            eprintln!("usage: scaffold DAY (1-25)");
+            process::exit(2);
+        }
+    };
+
+    match scaffold(Path::new(env!("CARGO_MANIFEST_DIR")), day) {
+        Ok(log) => log.iter().for_each(|line| println!("{line}")),
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod day01;\n// Insert before\naoc_lib! { year = 2022 }\n";
+    const SOLVER: &str = "solvers! {\n    // Insert solver before\n}\n\
+        const REGISTRY: &[Entry] = &[\n    // Insert entry before\n];\n";
+    const BENCHES: &str = "days! {\n    // Insert day before\n}\n";
+
+    #[test]
+    pub fn render_test() {
+        let day = render("#[aoc(dayN, part1)]\ncrate::answers::check(N);", 7);
+
+        assert_eq!(day, "#[aoc(day7, part1)]\ncrate::answers::check(7);");
+    }
+
+    #[test]
+    pub fn insert_line_test() {
+        let insert = |text, line| insert_line(text, "src/lib.rs", "// Insert before", line);
+
+        assert_eq!(
+            insert(LIB, "pub mod day02;").unwrap(),
+            "pub mod day01;\npub mod day02;\n// Insert before\naoc_lib! { year = 2022 }\n"
+        );
+        assert!(insert(LIB, "pub mod day01;").is_err());
+        assert!(insert("pub mod day01;\n", "pub mod day02;").is_err());
+    }
+
+    #[test]
+    pub fn scaffold_test() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/template.rs"), "#[aoc(dayN, part1)]\n").unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/solver.rs"), SOLVER).unwrap();
+        fs::create_dir_all(root.join("benches")).unwrap();
+        fs::write(root.join("benches/days.rs"), BENCHES).unwrap();
+
+        scaffold(&root, 3).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("src/day03.rs")).unwrap(),
+            "#[aoc(day3, part1)]\n"
+        );
+        assert!(root.join("input/2022/day3.txt").exists());
+        assert!(
+            fs::read_to_string(root.join("answers/2022.toml"))
+                .unwrap()
+                .contains("[day3]")
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("src/solver.rs")).unwrap(),
+            "solvers! {\n    3 => day03 str day03::Object, [part1, part2];\n    // Insert solver before\n}\n\
+             const REGISTRY: &[Entry] = &[\n    Entry::new::<Day<3>>(3, \"Day 3\"),\n    // Insert entry before\n];\n"
+        );
+        assert!(
+            fs::read_to_string(root.join("benches/days.rs"))
+                .unwrap()
+                .contains("    3 => day03::SAMPLE, [part1, part2];\n    // Insert day before")
+        );
+
+        // a second run must not touch the existing day
+        fs::write(root.join("src/day03.rs"), "edited").unwrap();
+        assert!(scaffold(&root, 3).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("src/day03.rs")).unwrap(),
+            "edited"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,11 +1,26 @@
+use std::{
+    fmt,
+    io::{self, BufRead},
+};
+
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{bytes::complete::tag, multi::separated_list1};
+use nom::{bytes::complete::tag, error::ErrorKind, multi::separated_list1};
 
-use crate::common::nom::{fold_separated_list0, nom_u32, process_input};
+use crate::{
+    common::{
+        heap_retain::accumulate_max_n,
+        nom::{fold_separated_list0, nom_u32},
+    },
+    parse::{try_process_input, ParseError},
+};
 
 #[aoc_generator(day01)]
 pub fn generator(input: &str) -> Vec<u32> {
-    process_input(separated_list1(
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    try_process_input(separated_list1(
         tag("\n\n"),
         fold_separated_list0(tag("\n"), nom_u32, || 0, |acc: u32, n| acc + n),
     ))(input)
@@ -26,16 +41,157 @@ fn solve<const N: usize>(inputs: &[u32]) -> u32 {
     inputs
         .iter()
         .copied()
-        .fold([0; N], crate::common::heap_retain::accumulate_max_n)
+        .fold([0; N], accumulate_max_n)
         .into_iter()
         .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// One elf in a [`top_n`] ranking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ranked {
+    /// 1 for the elf carrying the most.
+    pub rank: usize,
+    /// Position of the elf's group in the input, from 0.
+    pub elf: usize,
+    pub total: u32,
+}
+
+/// The `n` elves carrying the most, most first. Ties keep input order.
+pub fn top_n(inputs: &[u32], n: usize) -> Vec<Ranked> {
+    let mut elves: Vec<_> = inputs.iter().copied().enumerate().collect();
+    let by_total = |a: &(usize, u32), b: &(usize, u32)| b.1.cmp(&a.1).then(a.0.cmp(&b.0));
+
+    if n < elves.len() {
+        elves.select_nth_unstable_by(n, by_total);
+        elves.truncate(n);
+    }
+    elves.sort_unstable_by(by_total);
+
+    elves
+        .into_iter()
+        .zip(1..)
+        .map(|((elf, total), rank)| Ranked { rank, elf, total })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub elves: usize,
+    pub total: u64,
+    pub mean: f64,
+    pub median: f64,
+    /// Elves whose items add up to 0 calories. The generator can't tell an
+    /// empty group from one listing only `0`, so both count.
+    pub zero_total: usize,
+}
+
+/// Summary of an inventory, `None` when there are no elves.
+pub fn stats(inputs: &[u32]) -> Option<Stats> {
+    if inputs.is_empty() {
+        return None;
+    }
+
+    let mut sorted = inputs.to_vec();
+    sorted.sort_unstable();
+    let mid = sorted.len() / 2;
+    let median = if sorted.len().is_multiple_of(2) {
+        (f64::from(sorted[mid - 1]) + f64::from(sorted[mid])) / 2.0
+    } else {
+        f64::from(sorted[mid])
+    };
+    let total = inputs.iter().copied().map(u64::from).sum();
+
+    Some(Stats {
+        elves: inputs.len(),
+        total,
+        mean: total as f64 / inputs.len() as f64,
+        median,
+        zero_total: inputs.iter().filter(|&&x| x == 0).count(),
+    })
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "{e}"),
+            StreamError::Parse(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+/// The number on one line, or the column that's wrong with it.
+fn parse_calories(line: &[u8]) -> Result<u64, (usize, ErrorKind)> {
+    match line.iter().position(|b| !b.is_ascii_digit()) {
+        Some(0) => Err((0, ErrorKind::Digit)),
+        Some(p) => Err((p, ErrorKind::Eof)),
+        None => line
+            .iter()
+            .try_fold(0_u64, |acc, b| {
+                acc.checked_mul(10)?.checked_add(u64::from(b - b'0'))
+            })
+            .ok_or((0, ErrorKind::MapRes)),
+    }
+}
 
-    const SAMPLE: &str = r"1000
+/// The `N` largest totals, largest first, read one line at a time so the
+/// inventory never has to fit in memory. Blank lines separate the elves.
+pub fn top_totals<const N: usize>(mut reader: impl BufRead) -> Result<[u64; N], StreamError> {
+    let mut top = [0; N];
+    let mut total = None;
+    let mut line = Vec::new();
+    let mut line_num = 0;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let text = line.strip_suffix(b"\n").unwrap_or(&line);
+        let text = text.strip_suffix(b"\r").unwrap_or(text);
+
+        if text.is_empty() {
+            if let Some(total) = total.take() {
+                top = accumulate_max_n(top, total);
+            }
+        } else {
+            let sum = parse_calories(text).and_then(|calories| {
+                total
+                    .unwrap_or(0_u64)
+                    .checked_add(calories)
+                    .ok_or((0, ErrorKind::MapRes))
+            });
+            total = Some(sum.map_err(|(p, kind)| {
+                StreamError::Parse(
+                    ParseError::new(&text, &&text[p..], kind).with_line_offset(line_num),
+                )
+            })?);
+        }
+        line_num += 1;
+    }
+
+    if let Some(total) = total {
+        top = accumulate_max_n(top, total);
+    }
+
+    top.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(top)
+}
+
+pub const SAMPLE: &str = r"1000
 2000
 3000
 
@@ -50,6 +206,10 @@ mod tests {
 
 10000";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -57,6 +217,66 @@ mod tests {
         assert_eq!(generator(SAMPLE), &[6000, 4000, 11000, 24000, 10000]);
     }
 
+    #[test]
+    pub fn try_generator_test() {
+        let err = try_generator("1000\n2000\n\n3x00").unwrap_err();
+
+        assert_eq!((err.line, err.column), (4, 2));
+        assert_eq!(err.snippet, "3x00");
+    }
+
+    #[test]
+    pub fn top_n_test() {
+        let ranking = |inputs: &[u32], n| {
+            top_n(inputs, n)
+                .into_iter()
+                .map(|r| (r.rank, r.elf, r.total))
+                .collect::<Vec<_>>()
+        };
+        let inputs = generator(SAMPLE);
+
+        assert_eq!(
+            ranking(&inputs, 3),
+            [(1, 3, 24000), (2, 2, 11000), (3, 4, 10000)]
+        );
+        assert_eq!(ranking(&inputs, 10).len(), 5);
+        assert_eq!(ranking(&[5, 7, 5], 3), [(1, 1, 7), (2, 0, 5), (3, 2, 5)]);
+    }
+
+    #[test]
+    pub fn stats_test() {
+        let stats = stats(&generator("1000\n\n\n\n3000\n4000\n\n0\n\n2000")).unwrap();
+
+        assert_eq!(stats.elves, 5);
+        assert_eq!(stats.total, 10000);
+        assert_eq!(stats.mean, 2000.0);
+        assert_eq!(stats.median, 1000.0);
+        assert_eq!(stats.zero_total, 2);
+        assert_eq!(super::stats(&[]), None);
+    }
+
+    #[test]
+    pub fn top_totals_test() {
+        assert_eq!(
+            top_totals::<3>(SAMPLE.as_bytes()).unwrap(),
+            [24000, 11000, 10000]
+        );
+        assert_eq!(
+            top_totals::<1>("4000000000\r\n4000000000\r\n\r\n1\r\n".as_bytes()).unwrap(),
+            [8_000_000_000]
+        );
+        assert_eq!(
+            top_totals::<4>("1\n\n3\n\n2\n\n5\n\n4\n".as_bytes()).unwrap(),
+            [5, 4, 3, 2]
+        );
+
+        let Err(StreamError::Parse(err)) = top_totals::<1>("1000\n\n20x0\n".as_bytes()) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!((err.line, err.column), (3, 3));
+        assert_eq!(err.snippet, "20x0");
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 24000);
@@ -68,18 +288,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day1.txt");
-        const ANSWERS: (u32, u32) = (74198, 209914);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(1);
         }
     }
 }
@@ -1,49 +1,224 @@
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
 use nom::{
-    bytes::complete::{tag, take},
-    combinator::map,
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{map, value},
+    IResult, Parser,
 };
 
-use crate::common::nom::{nom_lines, process_input};
+use crate::parse::{try_process_lines, ParseError};
+
+/// One of `letters`, parsed as its index.
+fn letter<'i>(letters: &str) -> impl FnMut(&'i [u8]) -> IResult<&'i [u8], usize> {
+    let mut branches = letters
+        .char_indices()
+        .enumerate()
+        .map(|(i, (at, c))| value(i, tag(&letters[at..at + c.len_utf8()])))
+        .collect::<Vec<_>>();
+
+    move |input| alt(branches.as_mut_slice()).parse(input)
+}
+
+/// How to read the second column of the strategy guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guide {
+    /// The move to play (part 1).
+    Moves,
+    /// Whether to lose, draw or win (part 2).
+    Outcomes,
+}
+
+/// How a guide read with [`Guide::Moves`] plays out under one letter mapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakdown {
+    /// The move played for each of our letters.
+    pub mapping: Vec<usize>,
+    pub total: usize,
+    /// Score of each round.
+    pub rounds: Vec<usize>,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// Points scored in the rounds where each move was played.
+    pub points_per_move: Vec<usize>,
+}
+
+/// A hand game over an odd number of moves in a cycle, where every move beats
+/// the `(n - 1) / 2` moves before it and loses to the ones after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    /// The opponent's letter for each move, in cycle order.
+    pub opponent: String,
+    /// Our letter for each move, read with [`Guide::Moves`].
+    pub player: String,
+    /// Our letters for lose, draw and win, read with [`Guide::Outcomes`].
+    pub outcomes: String,
+    /// Points for playing each move.
+    pub move_scores: Vec<usize>,
+    /// Points for losing, drawing and winning.
+    pub outcome_scores: [usize; 3],
+}
+
+impl Game {
+    /// Panics unless there is an odd number (at least 3) of moves with a letter
+    /// and score each, and three outcome letters.
+    pub fn new(
+        opponent: &str,
+        player: &str,
+        outcomes: &str,
+        move_scores: Vec<usize>,
+        outcome_scores: [usize; 3],
+    ) -> Self {
+        let n = move_scores.len();
+        assert!(n >= 3 && n % 2 == 1, "need an odd number of moves, got {n}");
+        assert_eq!(opponent.chars().count(), n, "one opponent letter per move");
+        assert_eq!(player.chars().count(), n, "one player letter per move");
+        assert_eq!(outcomes.chars().count(), 3, "one letter per outcome");
+
+        Self {
+            opponent: opponent.to_string(),
+            player: player.to_string(),
+            outcomes: outcomes.to_string(),
+            move_scores,
+            outcome_scores,
+        }
+    }
+
+    /// Rock, Paper, Scissors as in the puzzle.
+    pub fn rps() -> Self {
+        Self::new("ABC", "XYZ", "XYZ", vec![1, 2, 3], [0, 3, 6])
+    }
+
+    /// Rock, Spock, Paper, Lizard, Scissors, scoring 1 to 5.
+    pub fn rpsls() -> Self {
+        Self::new("ABCDE", "VWXYZ", "XYZ", vec![1, 2, 3, 4, 5], [0, 3, 6])
+    }
+
+    pub fn moves(&self) -> usize {
+        self.move_scores.len()
+    }
+
+    /// 0, 1 or 2 when `me` loses, draws or wins against `you`.
+    pub fn outcome(&self, you: usize, me: usize) -> usize {
+        let n = self.moves();
+        match (me + n - you) % n {
+            0 => 1,
+            d if d <= n / 2 => 2,
+            _ => 0,
+        }
+    }
+
+    /// The move that gets `outcome` against `you`, the best scoring one if
+    /// there are several.
+    pub fn respond(&self, you: usize, outcome: usize) -> usize {
+        (0..self.moves())
+            .filter(|&me| self.outcome(you, me) == outcome)
+            .max_by(|&a, &b| {
+                self.move_scores[a]
+                    .cmp(&self.move_scores[b])
+                    .then(b.cmp(&a))
+            })
+            .unwrap()
+    }
+
+    pub fn round_score(&self, you: usize, me: usize) -> usize {
+        self.move_scores[me] + self.outcome_scores[self.outcome(you, me)]
+    }
+
+    /// Rounds as (opponent move, column two), both 0-based indices into this
+    /// game's letters.
+    pub fn try_parse(&self, input: &[u8], guide: Guide) -> Result<Vec<(usize, usize)>, ParseError> {
+        let response = match guide {
+            Guide::Moves => self.player.as_str(),
+            Guide::Outcomes => self.outcomes.as_str(),
+        };
+
+        try_process_lines(map(
+            (letter(&self.opponent), tag(" "), letter(response)),
+            |(l, _, r)| (l, r),
+        ))(input)
+    }
+
+    /// Play rounds read with [`Guide::Moves`], but with our letter `i` meaning
+    /// move `mapping[i]`.
+    pub fn breakdown(&self, rounds: &[(usize, usize)], mapping: &[usize]) -> Breakdown {
+        let mut res = Breakdown {
+            mapping: mapping.to_vec(),
+            total: 0,
+            rounds: Vec::with_capacity(rounds.len()),
+            wins: 0,
+            draws: 0,
+            losses: 0,
+            points_per_move: vec![0; self.moves()],
+        };
+
+        for &(you, letter) in rounds {
+            let me = mapping[letter];
+            let score = self.round_score(you, me);
+            match self.outcome(you, me) {
+                0 => res.losses += 1,
+                1 => res.draws += 1,
+                _ => res.wins += 1,
+            }
+            res.total += score;
+            res.rounds.push(score);
+            res.points_per_move[me] += score;
+        }
+
+        res
+    }
+
+    /// The letter mapping that scores the most, trying every permutation. On a
+    /// tie the first in lexicographic order wins, so the guide's own reading
+    /// is kept when it's as good as any.
+    pub fn best_mapping(&self, rounds: &[(usize, usize)]) -> Breakdown {
+        (0..self.moves())
+            .permutations(self.moves())
+            .map(|mapping| self.breakdown(rounds, &mapping))
+            .reduce(|best, b| if b.total > best.total { b } else { best })
+            .unwrap()
+    }
+
+    pub fn score(&self, rounds: &[(usize, usize)], guide: Guide) -> usize {
+        rounds
+            .iter()
+            .map(|&(you, r)| match guide {
+                Guide::Moves => self.round_score(you, r),
+                Guide::Outcomes => self.round_score(you, self.respond(you, r)),
+            })
+            .sum()
+    }
+}
 
 #[aoc_generator(day2)]
 pub fn generator(input: &[u8]) -> Vec<(usize, usize)> {
-    process_input(nom_lines(map(
-        (
-            map(take::<_, _, ()>(1usize), |x: &[u8]| {
-                usize::from(x[0] - b'A') + 1
-            }),
-            tag(" "),
-            map(take(1usize), |x: &[u8]| usize::from(x[0] - b'X') + 1),
-        ),
-        |(l, _, r)| (l, r),
-    )))(input)
+    try_generator(input).unwrap()
+}
+
+/// Both columns as 0-based indices: rock, paper, scissors or lose, draw, win.
+pub fn try_generator(input: &[u8]) -> Result<Vec<(usize, usize)>, ParseError> {
+    Game::rps().try_parse(input, Guide::Moves)
 }
 
 #[aoc(day2, part1)]
 pub fn part1(inputs: &[(usize, usize)]) -> usize {
-    inputs
-        .iter()
-        .map(|&(you, me)| me + 3 * ((4 + me - you) % 3))
-        .sum()
+    Game::rps().score(inputs, Guide::Moves)
 }
 
 #[aoc(day2, part2)]
 pub fn part2(inputs: &[(usize, usize)]) -> usize {
-    inputs
-        .iter()
-        .map(|&(you, outcome)| ((you + outcome) % 3 + 1) + 3 * (outcome - 1))
-        .sum()
+    Game::rps().score(inputs, Guide::Outcomes)
 }
 
+pub const SAMPLE: &[u8] = br"A Y
+B X
+C Z";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE: &[u8] = br"A Y
-B X
-C Z";
-
     #[test]
     pub fn input_test() {
         // println!("{:?}", generator(SAMPLE));
@@ -54,6 +229,50 @@ C Z";
         // );
     }
 
+    #[test]
+    pub fn try_generator_test() {
+        let err = try_generator(b"A Y\nB W").unwrap_err();
+
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, nom::error::ErrorKind::Tag);
+    }
+
+    #[test]
+    pub fn rpsls_test() {
+        let game = Game::rpsls();
+        let input = b"A W\nC W\nE W";
+
+        // Spock beats Rock, loses to Paper and beats Scissors
+        let rounds = game.try_parse(input, Guide::Moves).unwrap();
+        assert_eq!(game.score(&rounds, Guide::Moves), 8 + 2 + 8);
+
+        // Rock and Scissors both beat Lizard, Scissors scores more
+        let rounds = game.try_parse(b"D Z\nD Y", Guide::Outcomes).unwrap();
+        assert_eq!(rounds, [(3, 2), (3, 1)]);
+        assert_eq!(game.respond(3, 2), 4);
+        assert_eq!(game.score(&rounds, Guide::Outcomes), 11 + 7);
+
+        assert!(game.try_parse(b"A V", Guide::Outcomes).is_err());
+    }
+
+    #[test]
+    pub fn best_mapping_test() {
+        let game = Game::rps();
+        let rounds = generator(SAMPLE);
+
+        let guide = game.breakdown(&rounds, &[0, 1, 2]);
+        assert_eq!(guide.total, part1(&rounds));
+        assert_eq!(guide.rounds, [8, 1, 6]);
+        assert_eq!((guide.wins, guide.draws, guide.losses), (1, 1, 1));
+        assert_eq!(guide.points_per_move, [1, 8, 6]);
+
+        // X scissors, Y paper, Z rock wins every round
+        let best = game.best_mapping(&rounds);
+        assert_eq!(best.mapping, [2, 1, 0]);
+        assert_eq!(best.total, 24);
+        assert_eq!(best.wins, 3);
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 15);
@@ -65,18 +284,9 @@ C Z";
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day2.txt");
-        const ANSWERS: (usize, usize) = (13682, 12881);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n').as_bytes();
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(2);
         }
     }
 }
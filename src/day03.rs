@@ -1,7 +1,10 @@
+use std::fmt;
+
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{character::complete::alpha1, combinator::map};
+use bit_set::BitSet;
+use nom::{bytes::complete::take_till1, combinator::map};
 
-use crate::common::nom::{nom_lines, process_input};
+use crate::parse::{try_process_lines, ParseError};
 
 // Amount to subtract to
 const OFFSET: u8 = 64;
@@ -20,7 +23,13 @@ fn calc_priority(set: u64) -> u64 {
 
 #[aoc_generator(day3)]
 pub fn generator(input: &str) -> Vec<String> {
-    process_input(nom_lines(map(alpha1::<_, ()>, |s: &str| s.to_string())))(input)
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<String>, ParseError> {
+    try_process_lines(map(take_till1(char::is_whitespace), |s: &str| {
+        s.to_string()
+    }))(input)
 }
 
 #[aoc(day3, part1)]
@@ -61,17 +70,232 @@ pub fn part2(rucksacks: &[String]) -> u64 {
         .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Priority of a puzzle item, `None` for anything but ASCII letters.
+pub fn priority(item: char) -> Option<u64> {
+    match item {
+        'a'..='z' => Some(u64::from(item) - u64::from('a') + 1),
+        'A'..='Z' => Some(u64::from(item) - u64::from('A') + 27),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalyzeError {
+    /// A rucksack's items don't split evenly into its compartments.
+    Uneven { rucksack: usize },
+    /// The last group has fewer rucksacks than the group size.
+    IncompleteGroup { group: usize },
+    /// Nothing is shared by rucksack or group `index`.
+    NoCommonItem { index: usize },
+    /// More than one item is shared by rucksack or group `index`.
+    SeveralCommonItems { index: usize, items: Vec<char> },
+}
+
+impl fmt::Display for AnalyzeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalyzeError::Uneven { rucksack } => {
+                write!(
+                    f,
+                    "rucksack {rucksack} doesn't split into equal compartments"
+                )
+            }
+            AnalyzeError::IncompleteGroup { group } => write!(f, "group {group} is incomplete"),
+            AnalyzeError::NoCommonItem { index } => write!(f, "{index}: no common item"),
+            AnalyzeError::SeveralCommonItems { index, items } => {
+                write!(f, "{index}: several common items {items:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for AnalyzeError {}
 
-    const SAMPLE: &str = r"vJrwpWtwJgWrhcsFMMfFFhFp
+/// Finds the shared items for any alphabet (anything that's a `char`), number
+/// of compartments and group size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Analyzer {
+    pub compartments: usize,
+    pub group_size: usize,
+}
+
+impl Default for Analyzer {
+    fn default() -> Self {
+        Self {
+            compartments: 2,
+            group_size: 3,
+        }
+    }
+}
+
+/// The distinct items being compared, numbered from 0 so a set needs a bit per
+/// kind of item rather than per codepoint up to the largest one.
+struct Alphabet(Vec<char>);
+
+impl Alphabet {
+    fn new(items: impl IntoIterator<Item = char>) -> Self {
+        let mut items: Vec<char> = items.into_iter().collect();
+        items.sort_unstable();
+        items.dedup();
+        Self(items)
+    }
+
+    /// `items` must all be in the alphabet.
+    fn item_set(&self, items: impl IntoIterator<Item = char>) -> BitSet {
+        items
+            .into_iter()
+            .map(|c| self.0.binary_search(&c).expect("item outside the alphabet"))
+            .collect()
+    }
+}
+
+/// The one item in every set.
+fn common_item(
+    index: usize,
+    alphabet: &Alphabet,
+    mut sets: impl Iterator<Item = BitSet>,
+) -> Result<char, AnalyzeError> {
+    let mut common = sets.next().unwrap_or_default();
+    for set in sets {
+        common.intersect_with(&set);
+    }
+
+    let items: Vec<char> = common.iter().map(|i| alphabet.0[i]).collect();
+    match items[..] {
+        [item] => Ok(item),
+        [] => Err(AnalyzeError::NoCommonItem { index }),
+        _ => Err(AnalyzeError::SeveralCommonItems { index, items }),
+    }
+}
+
+impl Analyzer {
+    /// The item found in every compartment of rucksack number `index`.
+    pub fn misplaced_item(&self, index: usize, rucksack: &str) -> Result<char, AnalyzeError> {
+        let items: Vec<char> = rucksack.chars().collect();
+        if self.compartments == 0 || !items.len().is_multiple_of(self.compartments) {
+            return Err(AnalyzeError::Uneven { rucksack: index });
+        }
+
+        let size = items.len() / self.compartments;
+        let alphabet = Alphabet::new(items.iter().copied());
+        common_item(
+            index,
+            &alphabet,
+            items
+                .chunks(size.max(1))
+                .map(|c| alphabet.item_set(c.iter().copied())),
+        )
+    }
+
+    pub fn misplaced_items<S: AsRef<str>>(
+        &self,
+        rucksacks: &[S],
+    ) -> Result<Vec<char>, AnalyzeError> {
+        rucksacks
+            .iter()
+            .enumerate()
+            .map(|(i, rucksack)| self.misplaced_item(i, rucksack.as_ref()))
+            .collect()
+    }
+
+    /// The item every rucksack of each group has, one per group.
+    pub fn badges<S: AsRef<str>>(&self, rucksacks: &[S]) -> Result<Vec<char>, AnalyzeError> {
+        rucksacks
+            .chunks(self.group_size.max(1))
+            .enumerate()
+            .map(|(group, rucksacks)| {
+                if rucksacks.len() < self.group_size {
+                    return Err(AnalyzeError::IncompleteGroup { group });
+                }
+                let alphabet = Alphabet::new(rucksacks.iter().flat_map(|r| r.as_ref().chars()));
+                common_item(
+                    group,
+                    &alphabet,
+                    rucksacks
+                        .iter()
+                        .map(|r| alphabet.item_set(r.as_ref().chars())),
+                )
+            })
+            .collect()
+    }
+}
+
+pub const SAMPLE: &str = r"vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg
 wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
 ttgJtRGJQctTZtZT
 CrZsJsPPZsGzwwsLwLmpwMDw";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn analyzer_test() {
+        let rucksacks = generator(SAMPLE);
+        let analyzer = Analyzer::default();
+
+        let items = analyzer.misplaced_items(&rucksacks).unwrap();
+        assert_eq!(items, ['p', 'L', 'P', 'v', 't', 's']);
+        assert_eq!(
+            items.into_iter().filter_map(priority).sum::<u64>(),
+            part1(&rucksacks)
+        );
+
+        let badges = analyzer.badges(&rucksacks).unwrap();
+        assert_eq!(badges, ['r', 'Z']);
+        assert_eq!(
+            badges.into_iter().filter_map(priority).sum::<u64>(),
+            part2(&rucksacks)
+        );
+    }
+
+    #[test]
+    pub fn analyzer_config_test() {
+        // More than 64 kinds of item, three compartments, pairs of rucksacks
+        let analyzer = Analyzer {
+            compartments: 3,
+            group_size: 2,
+        };
+
+        assert_eq!(analyzer.misplaced_item(0, "αβ☃γ☃δ☃εζ"), Ok('☃'));
+        assert_eq!(analyzer.badges(&["a1€", "€b2"]), Ok(vec!['€']));
+        assert_eq!(
+            analyzer.misplaced_item(4, "abcdef"),
+            Err(AnalyzeError::NoCommonItem { index: 4 })
+        );
+        assert_eq!(
+            analyzer.badges(&["abc", "cba"]),
+            Err(AnalyzeError::SeveralCommonItems {
+                index: 0,
+                items: vec!['a', 'b', 'c']
+            })
+        );
+        assert_eq!(
+            analyzer.misplaced_item(1, "abcd"),
+            Err(AnalyzeError::Uneven { rucksack: 1 })
+        );
+        assert_eq!(
+            analyzer.badges(&["a", "a", "a"]),
+            Err(AnalyzeError::IncompleteGroup { group: 1 })
+        );
+    }
+
+    #[test]
+    pub fn generator_test() {
+        let rucksacks = generator("漢字字語\n語漢\n");
+
+        assert_eq!(rucksacks, ["漢字字語", "語漢"]);
+        assert_eq!(
+            Analyzer::default().misplaced_item(0, &rucksacks[0]),
+            Ok('字')
+        );
+
+        let err = try_generator("ab cd").unwrap_err();
+        assert_eq!((err.column, err.expected), (4, nom::error::ErrorKind::Eof));
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 157);
@@ -83,18 +307,9 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day3.txt");
-        const ANSWERS: (u64, u64) = (8153, 2342);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(3);
         }
     }
 }
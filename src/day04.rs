@@ -1,7 +1,13 @@
+use std::ops::RangeInclusive;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{bytes::complete::tag, combinator::map};
 
-use crate::common::nom::{nom_lines, nom_u32, process_input};
+use crate::{
+    common::nom::nom_u32,
+    interval::IntervalSet,
+    parse::{try_process_lines, ParseError},
+};
 
 type Range = (u32, u32);
 
@@ -13,7 +19,11 @@ pub struct Assignments {
 
 #[aoc_generator(day4)]
 pub fn generator(input: &str) -> Vec<Assignments> {
-    process_input(nom_lines(map(
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<Assignments>, ParseError> {
+    try_process_lines(map(
         (
             nom_u32,
             tag("-"),
@@ -27,7 +37,7 @@ pub fn generator(input: &str) -> Vec<Assignments> {
             let (one, two) = ((a, b), (x, y));
             Assignments { one, two }
         },
-    )))(input)
+    ))(input)
 }
 
 fn overlap1(a: Range, b: Range) -> bool {
@@ -38,6 +48,25 @@ fn overlap2(a: Range, b: Range) -> bool {
     a.0 <= b.1 && a.1 >= b.0
 }
 
+/// Every section at least one elf cleans.
+pub fn cleaned(assignments: &[Assignments]) -> IntervalSet<u32> {
+    assignments
+        .iter()
+        .flat_map(|&Assignments { one, two }| [one, two])
+        .map(|(start, end)| start..=end)
+        .collect()
+}
+
+/// Number of sections cleaned by at least one elf.
+pub fn covered(assignments: &[Assignments]) -> u128 {
+    cleaned(assignments).len()
+}
+
+/// The sections of `within` that nobody cleans.
+pub fn uncovered(assignments: &[Assignments], within: RangeInclusive<u32>) -> IntervalSet<u32> {
+    cleaned(assignments).gaps(within)
+}
+
 fn solve<F: Fn(Range, Range) -> bool>(assignments: &[Assignments], pred: F) -> usize {
     assignments
         .iter()
@@ -55,17 +84,17 @@ pub fn part2(assignments: &[Assignments]) -> usize {
     solve(assignments, overlap2)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"2-4,6-8
+pub const SAMPLE: &str = r"2-4,6-8
 2-3,4-5
 5-7,7-9
 2-8,3-7
 6-6,4-6
 2-6,4-8";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -73,6 +102,21 @@ mod tests {
         // assert_eq!(generator(SAMPLE), Object());
     }
 
+    #[test]
+    pub fn coverage_test() {
+        let assignments = generator(SAMPLE);
+
+        assert_eq!(covered(&assignments), 8);
+        assert_eq!(
+            uncovered(&assignments, 1..=10),
+            [1..=1, 10..=10].into_iter().collect()
+        );
+
+        let assignments = generator("1-2,8-9\n4-4,3-3");
+        assert_eq!(covered(&assignments), 6);
+        assert_eq!(uncovered(&assignments, 1..=9), IntervalSet::from(5..=7));
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 2);
@@ -84,18 +128,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day4.txt");
-        const ANSWERS: (usize, usize) = (464, 770);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(4);
         }
     }
 }
@@ -1,10 +1,10 @@
-use crate::common::{
-    nom::{nom_lines, nom_usize, process_input},
-    GetMutTwice,
+use crate::{
+    common::{nom::nom_usize, GetMutTwice},
+    parse::{try_process_lines, ParseError},
 };
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{bytes::complete::tag, combinator::map};
-use std::{convert::Infallible, str::FromStr};
+use nom::{bytes::complete::tag, combinator::map, error::ErrorKind};
+use std::{fmt, str::FromStr};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Crates {
@@ -12,18 +12,27 @@ pub struct Crates {
 }
 
 impl FromStr for Crates {
-    type Err = Infallible;
+    type Err = ParseError;
 
+    /// Read the drawing bottom up. Every slot is `[X]` or blank, and no crate
+    /// may float above an empty slot.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut lines = s.lines().rev();
-        let width = (lines.next().unwrap().len() + 2) / 4;
+        let Some(footer) = lines.next() else {
+            return Err(ParseError::new(&s, &&s[s.len()..], ErrorKind::Eof));
+        };
+        let width = (footer.len() + 2) / 4;
         let mut stacks = vec![Vec::with_capacity(64); width];
 
-        for line in lines {
-            let line = line.as_bytes();
-            for (name, stack) in line.chunks(4).zip(stacks.iter_mut()) {
-                if name[1] != b' ' {
-                    stack.push(name[1])
+        for (level, line) in lines.enumerate() {
+            let bytes = line.as_bytes();
+            for (i, (slot, stack)) in bytes.chunks(4).zip(stacks.iter_mut()).enumerate() {
+                let at = |kind| ParseError::new(&s, &&line[i * 4..], kind);
+                match slot {
+                    [b'[', name, b']', ..] if stack.len() == level => stack.push(*name),
+                    [b'[', _, b']', ..] => return Err(at(ErrorKind::Verify)),
+                    _ if slot.iter().all(|&b| b == b' ') => {}
+                    _ => return Err(at(ErrorKind::Char)),
                 }
             }
         }
@@ -32,6 +41,43 @@ impl FromStr for Crates {
     }
 }
 
+/// The puzzle's drawing, without trailing spaces.
+impl fmt::Display for Crates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or_default();
+
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(&name) => format!("[{}]", name as char),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" ").trim_end())?;
+        }
+
+        let footer: Vec<String> = (1..=self.stacks.len()).map(|n| format!("{n:^3}")).collect();
+        write!(f, "{}", footer.join(" ").trim_end())
+    }
+}
+
+impl Crates {
+    /// The top crate of each stack, a space for empty ones.
+    pub fn tops(&self) -> String {
+        let v = self
+            .stacks
+            .iter()
+            .map(|s| *s.last().unwrap_or(&b' '))
+            .collect();
+
+        // SAFETY: We're only dealing with ascii
+        unsafe { String::from_utf8_unchecked(v) }
+    }
+}
+
+/// Stacks are numbered from 1, as in the puzzle.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Move {
     count: usize,
@@ -39,8 +85,8 @@ pub struct Move {
     to_stack: usize,
 }
 
-fn parse_moves(s: &str) -> Vec<Move> {
-    process_input(nom_lines(map(
+fn parse_moves(s: &str) -> Result<Vec<Move>, ParseError> {
+    try_process_lines(map(
         (
             tag("move "),
             nom_usize,
@@ -51,10 +97,10 @@ fn parse_moves(s: &str) -> Vec<Move> {
         ),
         |(_, count, _, from_stack, _, to_stack)| Move {
             count,
-            from_stack: from_stack - 1,
-            to_stack: to_stack - 1,
+            from_stack,
+            to_stack,
         },
-    )))(s)
+    ))(s)
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -63,64 +109,218 @@ pub struct Input {
     moves: Vec<Move>,
 }
 
-impl Input {
-    fn process_moves1(&mut self) {
-        for &m in &self.moves {
-            let start = self.crates.stacks[m.from_stack].len() - m.count;
-            let (f, t) = self.crates.stacks.get_mut_twice(m.from_stack, m.to_stack);
-            t.extend(f.drain(start..).rev());
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crane {
+    /// Moves crates one at a time, reversing their order.
+    CrateMover9000,
+    /// Moves all the crates at once, keeping their order.
+    CrateMover9001,
+}
+
+/// Why a move can't be made. Stacks are numbered from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveError {
+    UnknownStack {
+        stack: usize,
+    },
+    SameStack {
+        stack: usize,
+    },
+    /// The source stack has fewer than `count` crates.
+    EmptyStack {
+        stack: usize,
+        count: usize,
+        available: usize,
+    },
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::UnknownStack { stack } => write!(f, "there is no stack {stack}"),
+            MoveError::SameStack { stack } => {
+                write!(f, "can't move from stack {stack} onto itself")
+            }
+            MoveError::EmptyStack {
+                stack,
+                count,
+                available,
+            } => write!(
+                f,
+                "can't take {count} crates from stack {stack}, it has {available}"
+            ),
         }
     }
+}
 
-    fn process_moves2(&mut self) {
-        for &m in &self.moves {
-            let start = self.crates.stacks[m.from_stack].len() - m.count;
-            let (f, t) = self.crates.stacks.get_mut_twice(m.from_stack, m.to_stack);
-            t.extend(f.drain(start..));
+impl std::error::Error for MoveError {}
+
+/// Runs moves on a copy of the crates, remembering them so the crane can be
+/// stepped back and forth.
+#[derive(Debug, Clone)]
+pub struct CraneSimulator {
+    crane: Crane,
+    crates: Crates,
+    history: Vec<(usize, usize, usize)>,
+    step: usize,
+}
+
+impl CraneSimulator {
+    pub fn new(crates: &Crates, crane: Crane) -> Self {
+        Self {
+            crane,
+            crates: crates.clone(),
+            history: Vec::new(),
+            step: 0,
         }
     }
 
-    fn read_tops(&self) -> String {
-        let v = self
-            .crates
-            .stacks
-            .iter()
-            .map(|s| *s.last().unwrap_or(&b' '))
-            .collect();
+    /// Check `m` against the current state, returning 0-based `(count, from, to)`.
+    fn validate(&self, m: Move) -> Result<(usize, usize, usize), MoveError> {
+        let index = |stack: usize| {
+            stack
+                .checked_sub(1)
+                .filter(|&i| i < self.crates.stacks.len())
+                .ok_or(MoveError::UnknownStack { stack })
+        };
+        let (from, to) = (index(m.from_stack)?, index(m.to_stack)?);
+        if from == to {
+            return Err(MoveError::SameStack {
+                stack: m.from_stack,
+            });
+        }
+        let available = self.crates.stacks[from].len();
+        if available < m.count {
+            return Err(MoveError::EmptyStack {
+                stack: m.from_stack,
+                count: m.count,
+                available,
+            });
+        }
 
-        // SAFETY: We're only dealing with ascii
-        unsafe { String::from_utf8_unchecked(v) }
+        Ok((m.count, from, to))
+    }
+
+    fn lift(&mut self, count: usize, from: usize, to: usize) {
+        let start = self.crates.stacks[from].len() - count;
+        let (f, t) = self.crates.stacks.get_mut_twice(from, to);
+        match self.crane {
+            Crane::CrateMover9000 => t.extend(f.drain(start..).rev()),
+            Crane::CrateMover9001 => t.extend(f.drain(start..)),
+        }
+    }
+
+    /// Make one move. Any undone moves after the current step are forgotten.
+    pub fn apply(&mut self, m: Move) -> Result<(), MoveError> {
+        let (count, from, to) = self.validate(m)?;
+        self.history.truncate(self.step);
+        self.history.push((count, from, to));
+        self.lift(count, from, to);
+        self.step += 1;
+
+        Ok(())
+    }
+
+    /// Make all `moves`, stopping at the first invalid one. On error,
+    /// [`CraneSimulator::step`] is the index of the move that failed.
+    pub fn run(&mut self, moves: &[Move]) -> Result<(), MoveError> {
+        moves.iter().try_for_each(|&m| self.apply(m))
+    }
+
+    /// Undo the last move, returning false at the start.
+    pub fn back(&mut self) -> bool {
+        let Some(step) = self.step.checked_sub(1) else {
+            return false;
+        };
+        let (count, from, to) = self.history[step];
+        // lifting the crates back reverses them again for the CrateMover 9000
+        self.lift(count, to, from);
+        self.step = step;
+        true
+    }
+
+    /// Redo the next recorded move, returning false at the end.
+    pub fn forward(&mut self) -> bool {
+        let Some(&(count, from, to)) = self.history.get(self.step) else {
+            return false;
+        };
+        self.lift(count, from, to);
+        self.step += 1;
+        true
+    }
+
+    /// Go to the state after `step` moves, as far as the history allows.
+    pub fn seek(&mut self, step: usize) {
+        while self.step > step && self.back() {}
+        while self.step < step && self.forward() {}
+    }
+
+    /// Number of moves made so far.
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Number of moves recorded, including undone ones.
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    pub fn crates(&self) -> &Crates {
+        &self.crates
+    }
+
+    pub fn tops(&self) -> String {
+        self.crates.tops()
+    }
+
+    /// The tops after `step` moves, or `None` past the recorded history.
+    pub fn tops_at(&self, step: usize) -> Option<String> {
+        (step <= self.len()).then(|| {
+            let mut sim = self.clone();
+            sim.seek(step);
+            sim.tops()
+        })
     }
 }
 
 #[aoc_generator(day5)]
 pub fn generator(input: &str) -> Input {
-    let (crates, moves) = input.split_once("\n\n").unwrap();
-    let crates = crates.trim_end().parse().unwrap();
-    let moves = parse_moves(moves);
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Input, ParseError> {
+    let Some((crates, moves)) = input.split_once("\n\n") else {
+        // the drawing and the moves must be separated by a blank line
+        let end = &input[input.len()..];
+        return Err(ParseError::new(&input, &end, ErrorKind::Tag));
+    };
+    let moves = parse_moves(moves).map_err(|e| e.with_line_offset(crates.lines().count() + 1))?;
+    let crates = crates.trim_end().parse()?;
 
-    Input { crates, moves }
+    Ok(Input { crates, moves })
+}
+
+pub fn try_solve(inputs: &Input, crane: Crane) -> Result<String, MoveError> {
+    let mut sim = CraneSimulator::new(&inputs.crates, crane);
+    sim.run(&inputs.moves)?;
+    Ok(sim.tops())
 }
 
 #[aoc(day5, part1)]
 pub fn part1(inputs: &Input) -> String {
-    let mut inputs = inputs.clone();
-    inputs.process_moves1();
-    inputs.read_tops()
+    try_solve(inputs, Crane::CrateMover9000).unwrap_or_else(|e| panic!("{e}"))
 }
 
 #[aoc(day5, part2)]
 pub fn part2(inputs: &Input) -> String {
-    let mut inputs = inputs.clone();
-    inputs.process_moves2();
-    inputs.read_tops()
+    try_solve(inputs, Crane::CrateMover9001).unwrap_or_else(|e| panic!("{e}"))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"
+pub const SAMPLE: &str = r"
     [D]
 [N] [C]
 [Z] [M] [P]
@@ -131,6 +331,10 @@ move 3 from 1 to 3
 move 2 from 2 to 1
 move 1 from 1 to 2";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         // println!("{:?}", generator(SAMPLE));
@@ -138,6 +342,111 @@ move 1 from 1 to 2";
         // assert_eq!(generator(SAMPLE), Object());
     }
 
+    #[test]
+    pub fn try_generator_test() {
+        let err = try_generator(&SAMPLE.replace("move 2", "mvoe 2")).unwrap_err();
+
+        assert_eq!(err.line, 9);
+        assert_eq!(err.snippet, "mvoe 2 from 2 to 1");
+
+        let err = try_generator(&SAMPLE.replace("[N]", "[N")).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected),
+            (3, 1, ErrorKind::Char)
+        );
+
+        // [D] would float above the empty top of stack 2
+        let err = try_generator(&SAMPLE.replace("[N] [C]", "[N]    ")).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected),
+            (2, 5, ErrorKind::Verify)
+        );
+
+        let err = try_generator("\n\nmove 1 from 1 to 2").unwrap_err();
+        assert_eq!(err.expected, ErrorKind::Eof);
+    }
+
+    #[test]
+    pub fn display_test() {
+        let input = generator(SAMPLE);
+        let drawing = SAMPLE.split_once("\n\n").unwrap().0.trim_matches('\n');
+
+        assert_eq!(input.crates.to_string(), drawing);
+
+        let mut sim = CraneSimulator::new(&input.crates, Crane::CrateMover9000);
+        sim.run(&input.moves[..2]).unwrap();
+        let drawing = sim.crates().to_string();
+        assert_eq!(
+            drawing,
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3"
+        );
+        assert_eq!(drawing.parse::<Crates>().unwrap(), *sim.crates());
+    }
+
+    #[test]
+    pub fn simulator_test() {
+        let input = generator(SAMPLE);
+        let mut sim = CraneSimulator::new(&input.crates, Crane::CrateMover9000);
+        sim.run(&input.moves).unwrap();
+
+        assert_eq!(sim.tops(), "CMZ");
+        assert_eq!(
+            (0..=4).map(|i| sim.tops_at(i).unwrap()).collect::<Vec<_>>(),
+            ["NDP", "DCP", " CZ", "M Z", "CMZ"]
+        );
+        assert_eq!(sim.tops_at(5), None);
+
+        sim.seek(1);
+        assert_eq!((sim.step(), sim.tops().as_str()), (1, "DCP"));
+        assert!(sim.back() && !sim.back());
+        assert_eq!(sim.crates(), &input.crates);
+
+        // a new move drops the undone ones
+        sim.forward();
+        sim.apply(input.moves[3]).unwrap();
+        assert_eq!((sim.len(), sim.tops().as_str()), (2, "NDP"));
+        assert!(!sim.forward());
+    }
+
+    #[test]
+    pub fn move_error_test() {
+        let input = generator(SAMPLE);
+        let mut sim = CraneSimulator::new(&input.crates, Crane::CrateMover9001);
+        let m = |count, from_stack, to_stack| Move {
+            count,
+            from_stack,
+            to_stack,
+        };
+
+        assert_eq!(
+            sim.apply(m(1, 4, 1)),
+            Err(MoveError::UnknownStack { stack: 4 })
+        );
+        assert_eq!(
+            sim.apply(m(1, 1, 0)),
+            Err(MoveError::UnknownStack { stack: 0 })
+        );
+        assert_eq!(
+            sim.apply(m(1, 2, 2)),
+            Err(MoveError::SameStack { stack: 2 })
+        );
+
+        let err = sim.run(&[m(1, 3, 1), m(2, 3, 1)]).unwrap_err();
+        assert_eq!(
+            err,
+            MoveError::EmptyStack {
+                stack: 3,
+                count: 2,
+                available: 0
+            }
+        );
+        assert_eq!(sim.step(), 1);
+        assert_eq!(
+            err.to_string(),
+            "can't take 2 crates from stack 3, it has 0"
+        );
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), "CMZ");
@@ -149,18 +458,9 @@ move 1 from 1 to 2";
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day5.txt");
-        const ANSWERS: (&str, &str) = ("HNSNMTLHQ", "RNLFDJMCT");
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(5);
         }
     }
 }
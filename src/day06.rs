@@ -1,51 +1,108 @@
+use std::{
+    collections::VecDeque,
+    io::{self, BufReader, Read},
+};
+
 use aoc_runner_derive::aoc;
 
-fn unique(s: &[u8]) -> Result<(), usize> {
-    for i in (1..s.len()).rev() {
-        for j in (0..i).rev() {
-            if s[i] == s[j] {
-                return Err(j + 1);
-            }
+pub const START_OF_PACKET: usize = 4;
+pub const START_OF_MESSAGE: usize = 14;
+
+/// Watches a signal one byte at a time for runs of `window` distinct bytes.
+#[derive(Debug, Clone)]
+pub struct MarkerDetector {
+    window: usize,
+    recent: VecDeque<u8>,
+    counts: [usize; 256],
+    /// How many byte values appear more than once in `recent`.
+    repeated: usize,
+    offset: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "markers need at least one byte");
+        Self {
+            window,
+            recent: VecDeque::with_capacity(window),
+            counts: [0; 256],
+            repeated: 0,
+            offset: 0,
         }
     }
-    Ok(())
-}
 
-fn solve<const N: usize>(input: &[u8]) -> usize {
-    let mut pos = 0;
+    /// Feed the next byte. Returns the number of bytes read so far when the
+    /// last `window` of them are all different.
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        if self.recent.len() == self.window {
+            let old = self.recent.pop_front().unwrap() as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 1 {
+                self.repeated -= 1;
+            }
+        }
 
-    loop {
-        assert!(pos + N < input.len());
-        if let Err(x) = unique(&input[pos..(pos + N)]) {
-            pos += x;
-        } else {
-            break pos + N;
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.repeated += 1;
         }
+        self.recent.push_back(byte);
+        self.offset += 1;
+
+        (self.recent.len() == self.window && self.repeated == 0).then_some(self.offset)
     }
 }
 
+/// Every marker offset in `signal`, lazily.
+pub fn markers(signal: impl IntoIterator<Item = u8>, window: usize) -> impl Iterator<Item = usize> {
+    let mut detector = MarkerDetector::new(window);
+    signal.into_iter().filter_map(move |b| detector.push(b))
+}
+
+/// [`markers`] for a signal read from `reader`, stopping after the first error.
+pub fn read_markers(reader: impl Read, window: usize) -> impl Iterator<Item = io::Result<usize>> {
+    let mut detector = MarkerDetector::new(window);
+    let mut failed = false;
+    BufReader::new(reader)
+        .bytes()
+        .map_while(move |b| match b {
+            _ if failed => None,
+            Ok(b) => Some(detector.push(b).map(Ok)),
+            Err(e) => {
+                failed = true;
+                Some(Some(Err(e)))
+            }
+        })
+        .flatten()
+}
+
+/// Where the first marker ends, `None` without one.
+pub fn first_marker(signal: &[u8], window: usize) -> Option<usize> {
+    markers(signal.iter().copied(), window).next()
+}
+
 #[aoc(day6, part1)]
 pub fn part1(input: &[u8]) -> usize {
-    solve::<4>(input)
+    first_marker(input, START_OF_PACKET).expect("no start-of-packet marker")
 }
 
 #[aoc(day6, part2)]
 pub fn part2(input: &[u8]) -> usize {
-    solve::<14>(input)
+    first_marker(input, START_OF_MESSAGE).expect("no start-of-message marker")
 }
 
+pub const SAMPLE: [&[u8]; 5] = [
+    b"mjqjpqmgbljsphdztnvjfqwrcgsmlb",
+    b"bvwbjplbgvbhsrlpgdmjqwftvncz",
+    b"nppdvjthqldpwncqszvftbrmjlhg",
+    b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
+    b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE: [&[u8]; 5] = [
-        b"mjqjpqmgbljsphdztnvjfqwrcgsmlb",
-        b"bvwbjplbgvbhsrlpgdmjqwftvncz",
-        b"nppdvjthqldpwncqszvftbrmjlhg",
-        b"nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg",
-        b"zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw",
-    ];
-
     #[test]
     pub fn input_test() {
         // println!("{:?}", generator(SAMPLE));
@@ -53,6 +110,28 @@ mod tests {
         // assert_eq!(generator(SAMPLE), Object());
     }
 
+    #[test]
+    pub fn markers_test() {
+        assert_eq!(markers(*b"abcabcd", 3).collect::<Vec<_>>(), [3, 4, 5, 6, 7]);
+        assert_eq!(markers(*b"aabccd", 2).collect::<Vec<_>>(), [3, 4, 6]);
+        assert_eq!(first_marker(b"abab", 3), None);
+        assert_eq!(first_marker(b"", 1), None);
+        assert_eq!(first_marker(b"abcd", 4), Some(4));
+
+        for input in SAMPLE {
+            let offsets: Vec<_> = read_markers(input, START_OF_MESSAGE)
+                .collect::<io::Result<_>>()
+                .unwrap();
+            let expected: Vec<_> = (START_OF_MESSAGE..=input.len())
+                .filter(|&end| {
+                    let window = &input[end - START_OF_MESSAGE..end];
+                    (1..window.len()).all(|i| !window[..i].contains(&window[i]))
+                })
+                .collect();
+            assert_eq!(offsets, expected);
+        }
+    }
+
     #[test]
     pub fn part1_test() {
         for (&input, ans) in SAMPLE.iter().zip([7, 5, 6, 10, 11]) {
@@ -68,18 +147,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day6.txt");
-        const ANSWERS: (usize, usize) = (1100, 2421);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n').as_bytes();
-            let output = input;
-
-            assert_eq!(part1(output), ANSWERS.0);
-            assert_eq!(part2(output), ANSWERS.1);
+            crate::answers::check(6);
         }
     }
 }
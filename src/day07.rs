@@ -1,92 +1,297 @@
+use std::collections::BTreeMap;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while1},
-    character::complete::alpha1,
-    combinator::{map, opt},
-    multi::fold_many0,
-    sequence::{preceded, terminated},
+    bytes::complete::tag,
+    combinator::{all_consuming, map, rest, verify},
+    error::ErrorKind,
+    sequence::{preceded, separated_pair},
     IResult, Parser,
 };
 
-use crate::common::nom::nom_usize;
+use crate::{common::nom::nom_usize, parse::ParseError};
+
+/// A file or a directory, whose children are sorted by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsNode {
+    File { size: usize },
+    Dir { children: BTreeMap<String, FsNode> },
+}
+
+impl FsNode {
+    fn dir() -> Self {
+        FsNode::Dir {
+            children: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(self, FsNode::Dir { .. })
+    }
+
+    /// Total size of everything below this node.
+    pub fn size(&self) -> usize {
+        match self {
+            FsNode::File { size } => *size,
+            FsNode::Dir { children } => children.values().map(FsNode::size).sum(),
+        }
+    }
+
+    fn children_mut(&mut self) -> Option<&mut BTreeMap<String, FsNode>> {
+        match self {
+            FsNode::File { .. } => None,
+            FsNode::Dir { children } => Some(children),
+        }
+    }
+
+    /// Look up a `/`-separated path. It's relative to this node, a leading `/`
+    /// is optional.
+    pub fn get(&self, path: &str) -> Option<&FsNode> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(self, |node, name| match node {
+                FsNode::File { .. } => None,
+                FsNode::Dir { children } => children.get(name),
+            })
+    }
 
-fn filename(s: &str) -> IResult<&str, &str> {
-    take_while1(|c: char| c == '.' || c.is_alphabetic())(s)
+    /// Every directory's absolute path and total size, children before their
+    /// parent like `du` prints them.
+    pub fn du(&self) -> Vec<(String, usize)> {
+        fn walk(node: &FsNode, path: &str, out: &mut Vec<(String, usize)>) -> usize {
+            let FsNode::Dir { children } = node else {
+                return node.size();
+            };
+            let total = children
+                .iter()
+                .map(|(name, child)| walk(child, &format!("{path}/{name}"), out))
+                .sum();
+            out.push((if path.is_empty() { "/" } else { path }.to_string(), total));
+            total
+        }
+
+        let mut out = Vec::new();
+        walk(self, "", &mut out);
+        out
+    }
+
+    /// [`FsNode::du`] as text, one `size<TAB>path` line per directory.
+    pub fn du_report(&self) -> String {
+        self.du()
+            .iter()
+            .map(|(path, size)| format!("{size}\t{path}\n"))
+            .collect()
+    }
 }
 
-fn dirname(s: &str) -> IResult<&str, &str> {
-    take_while1(|c: char| c == '/' || c == '.' || c.is_alphabetic())(s)
+enum Line<'a> {
+    Cd(&'a str),
+    Ls,
+    Dir(&'a str),
+    File(usize, &'a str),
 }
 
-fn ls(s: &str) -> IResult<&str, usize> {
-    preceded(
-        tag("$ ls\n"),
-        fold_many0(
-            terminated(
-                alt((
-                    map((tag("dir "), alpha1), |_| 0),
-                    map((nom_usize, tag(" "), filename), |(n, _, _)| n),
-                )),
-                opt(tag("\n")),
-            ),
-            || 0,
-            |acc, n| acc + n,
+fn line(s: &str) -> IResult<&str, Line<'_>> {
+    // a listed name is a single path component
+    let name = || verify(rest, |name: &str| !name.contains('/'));
+
+    all_consuming(alt((
+        map(preceded(tag("$ cd "), rest), Line::Cd),
+        map(tag("$ ls"), |_| Line::Ls),
+        map(preceded(tag("dir "), name()), Line::Dir),
+        map(
+            separated_pair(nom_usize, tag(" "), name()),
+            |(size, name)| Line::File(size, name),
         ),
-    )
+    )))
     .parse(s)
 }
 
-fn cd(s: &str) -> IResult<&str, &str> {
-    map((tag("$ cd "), dirname, tag("\n")), |(_, name, _)| name).parse(s)
+/// The directory `name` in `dir`, created if it's missing. Only a new entry
+/// allocates its key. `None` if `name` is a file.
+fn subdir<'a>(
+    dir: &'a mut BTreeMap<String, FsNode>,
+    name: &str,
+) -> Option<&'a mut BTreeMap<String, FsNode>> {
+    if !dir.contains_key(name) {
+        dir.insert(name.to_string(), FsNode::dir());
+    }
+    dir.get_mut(name)?.children_mut()
+}
+
+#[aoc_generator(day7)]
+pub fn generator(inputs: &str) -> FsNode {
+    try_generator(inputs).unwrap()
 }
 
-fn process(s: &mut &str, sizes: &mut Vec<usize>) -> usize {
-    let mut total = 0;
+/// Replay the transcript. Directories are created as they're listed or
+/// entered, and listing one again only adds what's new.
+pub fn try_generator(input: &str) -> Result<FsNode, ParseError> {
+    let mut root = FsNode::dir();
+    let mut cwd: Vec<&str> = Vec::new();
+
+    for l in input.lines().filter(|l| !l.is_empty()) {
+        let (_, parsed) = line(l).map_err(|e| match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => ParseError::new(&input, &e.input, e.code),
+            nom::Err::Incomplete(_) => ParseError::new(&input, &l, ErrorKind::Complete),
+        })?;
+        // a name that's already used by a node of the other kind
+        let clash = |name: &str| ParseError::new(&input, &name, ErrorKind::Verify);
+
+        if let Line::Cd(path) = parsed {
+            if path.starts_with('/') {
+                cwd.clear();
+            }
+            for name in path.split('/').filter(|name| !name.is_empty()) {
+                match name {
+                    "." => {}
+                    ".." => {
+                        cwd.pop();
+                    }
+                    _ => cwd.push(name),
+                }
+            }
+        }
+
+        let mut dir = root.children_mut().unwrap();
+        for &name in &cwd {
+            dir = subdir(dir, name).ok_or_else(|| clash(name))?;
+        }
 
-    while !s.is_empty() {
-        if let Ok((rem_s, name)) = cd(s) {
-            *s = rem_s;
-            match name {
-                ".." => break,
-                "/" => continue,
-                _ => total += process(s, sizes),
+        match parsed {
+            Line::Cd(_) | Line::Ls => {}
+            Line::Dir(name) => {
+                subdir(dir, name).ok_or_else(|| clash(name))?;
             }
-        } else if let Ok((rem_s, size)) = ls(s) {
-            *s = rem_s;
-            total += size;
-        } else {
-            panic!("unknown input: {s}")
+            Line::File(size, name) => match dir.get_mut(name) {
+                Some(FsNode::Dir { .. }) => return Err(clash(name)),
+                Some(node) => *node = FsNode::File { size },
+                None => {
+                    dir.insert(name.to_string(), FsNode::File { size });
+                }
+            },
         }
     }
 
-    sizes.push(total);
-    total
+    Ok(root)
 }
 
-#[aoc_generator(day7)]
-pub fn generator(mut inputs: &str) -> Vec<usize> {
-    let mut res = Vec::new();
-    process(&mut inputs, &mut res);
-    res
+#[aoc(day7, part1)]
+pub fn part1(root: &FsNode) -> usize {
+    root.du()
+        .into_iter()
+        .map(|(_, size)| size)
+        .filter(|&v| v < 100000)
+        .sum()
 }
 
-#[aoc(day7, part1)]
-pub fn part1(dirs: &[usize]) -> usize {
-    dirs.iter().filter(|&&v| v < 100000).sum()
+/// Picks directories to delete so an update fits. The root itself is never
+/// deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Planner {
+    pub capacity: usize,
+    pub required: usize,
 }
 
-#[aoc(day7, part2)]
-pub fn part2(dirs: &[usize]) -> usize {
-    let need = 30000000 - (70000000 - dirs[dirs.len() - 1]);
-    *dirs.iter().filter(|&&v| v > need).min().unwrap()
+impl Default for Planner {
+    fn default() -> Self {
+        Self {
+            capacity: 70000000,
+            required: 30000000,
+        }
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Paths and sizes of directories to delete.
+pub type Plan = Vec<(String, usize)>;
+
+impl Planner {
+    /// How much has to be deleted, 0 when there's already enough room.
+    pub fn to_free(&self, root: &FsNode) -> usize {
+        let free = self.capacity.saturating_sub(root.size());
+        self.required.saturating_sub(free)
+    }
 
-    const SAMPLE: &str = r"$ cd /
+    /// The smallest directory that frees enough space on its own.
+    pub fn smallest_dir(&self, root: &FsNode) -> Option<(String, usize)> {
+        let need = self.to_free(root);
+        let mut dirs = root.du();
+        dirs.pop(); // the root
+
+        dirs.into_iter()
+            .filter(|&(_, size)| size >= need)
+            .min_by_key(|&(_, size)| size)
+    }
+
+    /// The fewest directories, none inside another, that together free enough
+    /// space. Among those, the ones freeing the most.
+    pub fn fewest_dirs(&self, root: &FsNode) -> Option<Plan> {
+        // best[k]: the largest total of k non-nested directories under `node`
+        fn best(node: &FsNode, path: &str, is_root: bool) -> Vec<Option<(usize, Plan)>> {
+            let FsNode::Dir { children } = node else {
+                return vec![Some((0, Vec::new()))];
+            };
+
+            let mut acc = vec![Some((0, Vec::new()))];
+            for (name, child) in children.iter().filter(|(_, child)| child.is_dir()) {
+                let other = best(child, &format!("{path}/{name}"), false);
+                let mut merged = vec![None; acc.len() + other.len() - 1];
+                for (i, a) in acc.iter().enumerate() {
+                    for (j, b) in other.iter().enumerate() {
+                        let (Some((sa, pa)), Some((sb, pb))) = (a, b) else {
+                            continue;
+                        };
+                        if merged[i + j].as_ref().is_none_or(|(s, _)| sa + sb > *s) {
+                            merged[i + j] = Some((sa + sb, [&pa[..], &pb[..]].concat()));
+                        }
+                    }
+                }
+                acc = merged;
+            }
+
+            let size = node.size();
+            if !is_root {
+                if acc.len() < 2 {
+                    acc.push(None);
+                }
+                if acc[1].as_ref().is_none_or(|(s, _)| size > *s) {
+                    acc[1] = Some((size, vec![(path.to_string(), size)]));
+                }
+            }
+            acc
+        }
+
+        let need = self.to_free(root);
+        best(root, "", true)
+            .into_iter()
+            .flatten()
+            .find(|&(total, _)| total >= need)
+            .map(|(_, plan)| plan)
+    }
+
+    /// [`Planner::smallest_dir`] if there's one, [`Planner::fewest_dirs`]
+    /// otherwise. Empty when nothing needs deleting.
+    pub fn plan(&self, root: &FsNode) -> Option<Plan> {
+        if self.to_free(root) == 0 {
+            return Some(Vec::new());
+        }
+        match self.smallest_dir(root) {
+            Some(dir) => Some(vec![dir]),
+            None => self.fewest_dirs(root),
+        }
+    }
+}
+
+#[aoc(day7, part2)]
+pub fn part2(root: &FsNode) -> usize {
+    Planner::default()
+        .smallest_dir(root)
+        .expect("no directory is big enough")
+        .1
+}
+
+pub const SAMPLE: &str = r"$ cd /
 $ ls
 dir a
 14848514 b.txt
@@ -110,6 +315,10 @@ $ ls
 5626152 d.ext
 7214296 k";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -128,14 +337,106 @@ dir jvwfwrg
 dir tzwpllhq
 dir vglf
 28586 wzljr.zvp";
-        assert_eq!(ls(A).unwrap().1, 72939 + 236918 + 28586);
+        let root = generator(A);
+
+        assert_eq!(root.size(), 72939 + 236918 + 28586);
+        assert_eq!(root.get("/fcqv"), Some(&FsNode::dir()));
+        assert_eq!(
+            root.get("jlncjqh.csz"),
+            Some(&FsNode::File { size: 236918 })
+        );
     }
 
     #[test]
     pub fn cd_test() {
         const A: &str = "$ cd /
-";
-        assert_eq!(cd(A).unwrap().1, "/");
+$ cd a/b c
+$ ls
+10 file-2.tar.gz
+dir .cache
+$ cd /a
+$ ls
+dir b c
+5 README
+$ cd ./b c/../b c
+$ ls
+10 file-2.tar.gz";
+        let root = generator(A);
+
+        assert_eq!(root.size(), 15);
+        assert_eq!(root.get("/a/b c/.cache"), Some(&FsNode::dir()));
+        assert_eq!(root.get("/a/README/x"), None);
+        assert_eq!(
+            root.du_report(),
+            "0\t/a/b c/.cache\n10\t/a/b c\n15\t/a\n15\t/\n"
+        );
+
+        let err = try_generator("$ ls\n12 a\n$ cd a").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected),
+            (3, 6, ErrorKind::Verify)
+        );
+        let err = try_generator("$ ls\n12a").unwrap_err();
+        assert_eq!((err.line, err.snippet.as_str()), (2, "12a"));
+
+        // listed names can't smuggle in a path
+        let err = try_generator("$ ls\n5 a/b").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected),
+            (2, 3, ErrorKind::Verify)
+        );
+        let err = try_generator("$ ls\ndir a/b").unwrap_err();
+        assert_eq!((err.line, err.snippet.as_str()), (2, "dir a/b"));
+    }
+
+    #[test]
+    pub fn du_test() {
+        let root = generator(SAMPLE);
+
+        assert_eq!(
+            root.du(),
+            [
+                ("/a/e".to_string(), 584),
+                ("/a".to_string(), 94853),
+                ("/d".to_string(), 24933642),
+                ("/".to_string(), 48381165)
+            ]
+        );
+        assert_eq!(root.get("/a/e/i"), Some(&FsNode::File { size: 584 }));
+        assert_eq!(root.get("/a/e").map(FsNode::size), Some(584));
+    }
+
+    #[test]
+    pub fn planner_test() {
+        let root = generator(SAMPLE);
+        let dir = |path: &str, size| (path.to_string(), size);
+
+        let planner = Planner::default();
+        assert_eq!(planner.to_free(&root), 8381165);
+        assert_eq!(planner.plan(&root), Some(vec![dir("/d", 24933642)]));
+
+        // nothing but the root is big enough: /d and /a together are
+        let planner = Planner {
+            capacity: 48381165,
+            required: 25000000,
+        };
+        assert_eq!(planner.smallest_dir(&root), None);
+        assert_eq!(
+            planner.plan(&root),
+            Some(vec![dir("/a", 94853), dir("/d", 24933642)])
+        );
+
+        let planner = Planner {
+            capacity: 48381165,
+            required: 30000000,
+        };
+        assert_eq!(planner.plan(&root), None);
+
+        let planner = Planner {
+            capacity: 80000000,
+            required: 30000000,
+        };
+        assert_eq!(planner.plan(&root), Some(vec![]));
     }
 
     #[test]
@@ -149,18 +450,9 @@ dir vglf
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day7.txt");
-        const ANSWERS: (usize, usize) = (1182909, 2832508);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(7);
         }
     }
 }
@@ -1,88 +1,116 @@
-use crate::common::utils::neighbors;
+use crate::{
+    grid::{Grid, Pos},
+    parse::ParseError,
+};
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::error::ErrorKind;
 
 #[aoc_generator(day8)]
-pub fn generator(input: &str) -> Vec<Vec<u8>> {
-    input
-        .lines()
-        .map(|line| line.bytes().map(|b| b - b'0').collect())
-        .collect()
+pub fn generator(input: &str) -> Grid<u8> {
+    try_generator(input).unwrap()
 }
 
-fn check_visible(inputs: &[Vec<u8>], r: usize, c: usize, h: usize, w: usize) -> bool {
-    let height = inputs[r][c];
-
-    inputs[r][..c].iter().all(|&hx| hx < height)
-        || inputs[r][(c + 1)..w].iter().all(|&hx| hx < height)
-        || (0..r).map(|hi| inputs[hi][c]).all(|hx| hx < height)
-        || ((r + 1)..h).map(|hi| inputs[hi][c]).all(|hx| hx < height)
+pub fn try_generator(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::try_parse(input, |_, b| {
+        if b.is_ascii_digit() {
+            Ok(b - b'0')
+        } else {
+            Err(ErrorKind::Digit)
+        }
+    })
 }
 
-fn count_trees(i: impl Iterator<Item = u8>, height: u8) -> usize {
-    let mut count = 0;
-    for hx in i {
-        count += 1;
-        if hx >= height {
-            break;
+/// Look along one line of trees, updating `visible` and `scores` for the view
+/// back towards its start. The stack holds the trees not yet hidden behind a
+/// taller one, so each tree is pushed and popped once.
+fn sweep(
+    grid: &Grid<u8>,
+    line: impl Iterator<Item = Pos>,
+    visible: &mut Grid<bool>,
+    scores: &mut Grid<usize>,
+) {
+    let mut stack: Vec<(usize, u8)> = Vec::new();
+
+    for (i, pos) in line.enumerate() {
+        let height = grid[pos];
+        while stack.last().is_some_and(|&(_, h)| h < height) {
+            stack.pop();
         }
+
+        // no tree as tall before this one: visible, and it sees to the edge
+        let distance = match stack.last() {
+            Some(&(j, _)) => i - j,
+            None => {
+                visible[pos] = true;
+                i
+            }
+        };
+        scores[pos] *= distance;
+        stack.push((i, height));
     }
-    count
 }
 
-fn calculate_score(inputs: &[Vec<u8>], r: usize, c: usize, h: usize, w: usize) -> usize {
-    let height = inputs[r][c];
+/// Which trees are visible from outside the forest, and each tree's scenic
+/// score.
+pub fn survey(grid: &Grid<u8>) -> (Grid<bool>, Grid<usize>) {
+    let (w, h) = (grid.width(), grid.height());
+    let mut visible = Grid::new(w, h, false);
+    let mut scores = Grid::new(w, h, 1);
+
+    for r in 0..h {
+        sweep(grid, (0..w).map(|c| (r, c)), &mut visible, &mut scores);
+        sweep(
+            grid,
+            (0..w).rev().map(|c| (r, c)),
+            &mut visible,
+            &mut scores,
+        );
+    }
+    for c in 0..w {
+        sweep(grid, (0..h).map(|r| (r, c)), &mut visible, &mut scores);
+        sweep(
+            grid,
+            (0..h).rev().map(|r| (r, c)),
+            &mut visible,
+            &mut scores,
+        );
+    }
 
-    count_trees((0..r).rev().map(|hi| inputs[hi][c]), height)
-        * count_trees(inputs[r][..c].iter().rev().copied(), height)
-        * count_trees(((r + 1)..h).map(|hi| inputs[hi][c]), height)
-        * count_trees(inputs[r][(c + 1)..w].iter().copied(), height)
+    (visible, scores)
 }
 
-#[aoc(day8, part1)]
-pub fn part1(inputs: &[Vec<u8>]) -> usize {
-    let height = inputs.len();
-    let width = inputs[0].len();
-    let mut count = (width - 1) * 2 + (height - 1) * 2;
-
-    for r in 0..height {
-        for c in 0..height {
-            if neighbors(r, c, height, width).count() == 4
-                && check_visible(inputs, r, c, height, width)
-            {
-                count += 1;
-            }
-        }
-    }
-
-    count
+pub fn visibility(grid: &Grid<u8>) -> Grid<bool> {
+    survey(grid).0
 }
 
-#[aoc(day8, part2)]
-pub fn part2(inputs: &[Vec<u8>]) -> usize {
-    let height = inputs.len();
-    let width = inputs[0].len();
-    let mut max = 0;
-
-    for r in 0..height {
-        for c in 0..height {
-            let score = calculate_score(inputs, r, c, height, width);
-            max = max.max(score);
-        }
-    }
+pub fn scenic_scores(grid: &Grid<u8>) -> Grid<usize> {
+    survey(grid).1
+}
 
-    max
+#[aoc(day8, part1)]
+pub fn part1(inputs: &Grid<u8>) -> usize {
+    visibility(inputs).iter().filter(|&&v| v).count()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[aoc(day8, part2)]
+pub fn part2(inputs: &Grid<u8>) -> usize {
+    scenic_scores(inputs)
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+}
 
-    const SAMPLE: &str = r"30373
+pub const SAMPLE: &str = r"30373
 25512
 65332
 33549
 35390";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         // println!("{:?}", generator(SAMPLE));
@@ -90,6 +118,47 @@ mod tests {
         // assert_eq!(generator(SAMPLE), Object());
     }
 
+    #[test]
+    pub fn survey_test() {
+        let (visible, scores) = survey(&generator(SAMPLE));
+
+        assert_eq!(
+            visible.map(|&v| if v { b'#' } else { b'.' }).to_string(),
+            "#####\n###.#\n##.##\n#.#.#\n#####"
+        );
+        assert_eq!((scores[(1, 2)], scores[(3, 2)], scores[(0, 4)]), (4, 8, 0));
+    }
+
+    #[test]
+    pub fn rectangular_test() {
+        let grid = generator("3037325\n2551212\n6533208");
+        let (visible, scores) = survey(&grid);
+
+        // the obvious scan in each direction
+        for (r, c) in grid.positions() {
+            let height = grid[(r, c)];
+            let lines: [Vec<u8>; 4] = [
+                grid.row(r)[..c].iter().rev().copied().collect(),
+                grid.row(r)[c + 1..].to_vec(),
+                (0..r).rev().map(|i| grid[(i, c)]).collect(),
+                (r + 1..grid.height()).map(|i| grid[(i, c)]).collect(),
+            ];
+
+            let seen = lines.iter().any(|l| l.iter().all(|&x| x < height));
+            let score: usize = lines
+                .iter()
+                .map(|l| {
+                    l.iter()
+                        .position(|&x| x >= height)
+                        .map_or(l.len(), |p| p + 1)
+                })
+                .product();
+            assert_eq!((visible[(r, c)], scores[(r, c)]), (seen, score), "{r} {c}");
+        }
+        assert_eq!(part1(&grid), 19);
+        assert_eq!(part2(&grid), 4);
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 21);
@@ -101,18 +170,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day8.txt");
-        const ANSWERS: (usize, usize) = (1794, 199272);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(8);
         }
     }
 }
@@ -1,11 +1,16 @@
+use std::fmt;
+
 use aoc_runner_derive::{aoc, aoc_generator};
-use bitvec::prelude::BitArray;
-use nom::{
-    bytes::complete::{self, tag},
-    combinator::map,
+use nom::{bytes::complete::tag, character::complete::one_of, combinator::map};
+use rustc_hash::FxHashSet as HashSet;
+
+use crate::{
+    common::nom::nom_usize,
+    parse::{try_process_lines, ParseError},
 };
 
-use crate::common::nom::{nom_lines, nom_usize, process_input};
+/// A `(row, column)` position, rows growing upwards.
+pub type Point = (i32, i32);
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Move {
@@ -14,7 +19,7 @@ pub struct Move {
 }
 
 impl Move {
-    fn get(&self) -> (i16, i16, usize) {
+    fn get(&self) -> (i32, i32, usize) {
         let x = self.mag;
         match self.dir {
             b'U' => (1, 0, x),
@@ -26,47 +31,107 @@ impl Move {
     }
 }
 
-#[derive(Debug)]
-pub struct Snake<const N: usize> {
-    rope: [(i16, i16); N],
+/// A rope of any number of knots, all starting at the origin, remembering
+/// where each knot has been.
+#[derive(Debug, Clone)]
+pub struct Snake {
+    rope: Vec<Point>,
+    visited: Vec<HashSet<Point>>,
 }
 
-impl<const N: usize> Default for Snake<N> {
-    fn default() -> Self {
+impl Snake {
+    pub fn new(knots: usize) -> Self {
+        assert!(knots > 0, "a rope needs a head");
         Self {
-            rope: [Default::default(); N],
+            rope: vec![(0, 0); knots],
+            visited: vec![HashSet::from_iter([(0, 0)]); knots],
         }
     }
-}
 
-impl<const N: usize> Snake<N> {
+    /// Knot positions, head first.
+    pub fn knots(&self) -> &[Point] {
+        &self.rope
+    }
+
+    /// The positions each knot has visited, head first.
+    pub fn visited(&self) -> &[HashSet<Point>] {
+        &self.visited
+    }
+
+    /// Move the head by one cell and let the rest follow.
+    pub fn step(&mut self, (r, c): Point) {
+        self.rope[0] = (self.rope[0].0 + r, self.rope[0].1 + c);
+        self.visited[0].insert(self.rope[0]);
+
+        for x in 1..self.rope.len() {
+            // once a knot stays put, so does everything behind it
+            if !self.update_tail(x, self.rope[x - 1]) {
+                break;
+            }
+            self.visited[x].insert(self.rope[x]);
+        }
+    }
+
+    /// Run all the moves, returning how many positions the tail has visited.
     pub fn process_moves(&mut self, mv: &[Move]) -> usize {
-        let mut pos: BitArray<[usize; 1 << 11]> = BitArray::ZERO;
-        let mut count = 1;
-        pos.set(0, true);
+        for delta in steps(mv) {
+            self.step(delta);
+        }
+
+        self.visited.last().unwrap().len()
+    }
 
-        for m in mv {
-            let (r, c, mag) = m.get();
+    /// The rope after the first `step` head steps of `mv`.
+    pub fn after(mv: &[Move], knots: usize, step: usize) -> Self {
+        let mut snake = Self::new(knots);
+        for delta in steps(mv).take(step) {
+            snake.step(delta);
+        }
+        snake
+    }
 
-            'outer: for _ in 0..mag {
-                self.rope[0] = (self.rope[0].0 + r, self.rope[0].1 + c);
+    /// The smallest area holding everything every knot has visited.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::around(self.visited.iter().flatten().copied())
+    }
 
-                for x in 1..N {
-                    if !self.update_tail(x, self.rope[x - 1]) {
-                        continue 'outer;
-                    }
+    /// Draw the area `bounds` like the puzzle does: the knots (`H`, then `1`
+    /// to `9`, earlier knots on top), the start `s` and, if `trail` is a knot,
+    /// the cells it has visited as `#`.
+    pub fn render(&self, bounds: Bounds, trail: Option<usize>) -> String {
+        let trail = trail.map(|knot| &self.visited[knot]);
+        bounds.draw(|pos| {
+            if let Some(knot) = self.rope.iter().position(|&k| k == pos) {
+                match knot {
+                    0 => 'H',
+                    1..=9 => char::from(b'0' + knot as u8),
+                    _ => '+',
                 }
-
-                let mut r = unsafe { pos.get_unchecked_mut(cantor(self.rope[N - 1])) };
-                count += !*r as usize;
-                *r = true;
+            } else if pos == (0, 0) {
+                's'
+            } else if trail.is_some_and(|t| t.contains(&pos)) {
+                '#'
+            } else {
+                '.'
             }
-        }
+        })
+    }
 
-        count
+    /// Just the cells `knot` has visited and the start, like the puzzle's
+    /// final pictures.
+    pub fn render_trail(&self, bounds: Bounds, knot: usize) -> String {
+        bounds.draw(|pos| {
+            if pos == (0, 0) {
+                's'
+            } else if self.visited[knot].contains(&pos) {
+                '#'
+            } else {
+                '.'
+            }
+        })
     }
 
-    pub fn update_tail(&mut self, pos: usize, last: (i16, i16)) -> bool {
+    pub fn update_tail(&mut self, pos: usize, last: Point) -> bool {
         let d = (last.0 - self.rope[pos].0, last.1 - self.rope[pos].1);
 
         if d.0.abs() > 1 || d.1.abs() > 1 {
@@ -79,42 +144,105 @@ impl<const N: usize> Snake<N> {
     }
 }
 
-#[inline]
-fn cantor(p: (i16, i16)) -> usize {
-    crate::common::pairing::pair2d_b(p.0, p.1)
+/// The head's unit steps for `mv`.
+fn steps(mv: &[Move]) -> impl Iterator<Item = Point> + '_ {
+    mv.iter().flat_map(|m| {
+        let (r, c, mag) = m.get();
+        std::iter::repeat_n((r, c), mag)
+    })
+}
+
+/// An inclusive rectangle of the plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    /// The smallest rectangle holding all of `points` and the origin.
+    pub fn around(points: impl IntoIterator<Item = Point>) -> Self {
+        points.into_iter().fold(
+            Self {
+                min: (0, 0),
+                max: (0, 0),
+            },
+            |b, (r, c)| Self {
+                min: (b.min.0.min(r), b.min.1.min(c)),
+                max: (b.max.0.max(r), b.max.1.max(c)),
+            },
+        )
+    }
+
+    /// One line per row, the top row first.
+    fn draw(&self, mut cell: impl FnMut(Point) -> char) -> String {
+        let mut out = String::new();
+        for r in (self.min.0..=self.max.0).rev() {
+            if r != self.max.0 {
+                out.push('\n');
+            }
+            out.extend((self.min.1..=self.max.1).map(|c| cell((r, c))));
+        }
+        out
+    }
+}
+
+/// Every step of the moves as text, each move under a `== R 4 ==` header like
+/// in the puzzle, with the tail's trail so far. All frames share the same
+/// bounds. Frames are written to `out` as they're drawn, so long inputs can
+/// go straight to a file or stdout.
+pub fn animation(out: &mut impl fmt::Write, mv: &[Move], knots: usize) -> fmt::Result {
+    let mut snake = Snake::new(knots);
+    let bounds = {
+        let mut end = snake.clone();
+        end.process_moves(mv);
+        end.bounds()
+    };
+    let tail = Some(knots - 1);
+
+    writeln!(out, "== Initial State ==\n\n{}", snake.render(bounds, tail))?;
+    for m in mv {
+        writeln!(out, "\n== {} {} ==", m.dir as char, m.mag)?;
+        let (r, c, mag) = m.get();
+        for _ in 0..mag {
+            snake.step((r, c));
+            writeln!(out, "\n{}", snake.render(bounds, tail))?;
+        }
+    }
+    Ok(())
 }
 
 #[aoc_generator(day9)]
 pub fn generator(input: &str) -> Vec<Move> {
-    process_input(nom_lines(map(
-        (complete::take(1_usize), tag(" "), nom_usize::<&str>),
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<Move>, ParseError> {
+    try_process_lines(map(
+        (one_of("UDLR"), tag(" "), nom_usize::<&str>),
         |(dir, _, mag)| Move {
-            dir: dir.as_bytes()[0],
+            dir: dir as u8,
             mag,
         },
-    )))(input)
+    ))(input)
 }
 
-fn solve<const N: usize>(inputs: &[Move]) -> usize {
-    let mut snake = Snake::<N>::default();
+pub fn solve(inputs: &[Move], knots: usize) -> usize {
+    let mut snake = Snake::new(knots);
     snake.process_moves(inputs)
 }
 
 #[aoc(day9, part1)]
 pub fn part1(inputs: &[Move]) -> usize {
-    solve::<2>(inputs)
+    solve(inputs, 2)
 }
 
 #[aoc(day9, part2)]
 pub fn part2(inputs: &[Move]) -> usize {
-    solve::<10>(inputs)
+    solve(inputs, 10)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"R 4
+pub const SAMPLE: &str = r"R 4
 U 4
 L 3
 D 1
@@ -123,6 +251,10 @@ D 1
 L 5
 R 2";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     const SAMPLE2: &str = r"R 5
 U 8
 L 8
@@ -139,6 +271,62 @@ U 20";
         // assert_eq!(generator(SAMPLE), Object());
     }
 
+    #[test]
+    pub fn visited_test() {
+        let mut snake = Snake::new(10);
+        snake.process_moves(&generator(SAMPLE2));
+
+        let counts: Vec<usize> = snake.visited().iter().map(HashSet::len).collect();
+        // the R 17 crosses the U 8 at (5, 5)
+        assert_eq!(counts[0], 1 + 5 + 8 + 8 + 3 + 17 + 10 + 25 + 20 - 1);
+        assert_eq!(counts[9], 36);
+        assert!(counts.windows(2).all(|w| w[0] >= w[1]));
+        assert_eq!(snake.knots()[0], (15, -11));
+        assert!(snake.visited()[0].contains(&(-5, 12)));
+
+        // far beyond what an 11 bit pairing covered
+        let far = generator("R 5000\nU 3000\nL 10000");
+        assert_eq!(solve(&far, 1), 18001);
+        assert_eq!(solve(&far, 3), 17995);
+    }
+
+    #[test]
+    pub fn render_test() {
+        let moves = generator(SAMPLE);
+        let snake = Snake::after(&moves, 2, usize::MAX);
+        let bounds = snake.bounds();
+
+        assert_eq!(
+            snake.render(bounds, None),
+            "......\n......\n.1H...\n......\ns....."
+        );
+        assert_eq!(
+            snake.render_trail(bounds, 1),
+            "..##..\n...##.\n.####.\n....#.\ns###.."
+        );
+
+        // after U 4 the first 4 knots are lined up, the rest haven't moved
+        let snake = Snake::after(&moves, 10, 8);
+        assert_eq!(
+            snake.render(bounds, None),
+            "....H.\n....1.\n..432.\n.5....\n6....."
+        );
+    }
+
+    #[test]
+    pub fn animation_test() {
+        let moves = generator(SAMPLE);
+        let mut text = String::new();
+        animation(&mut text, &moves, 2).unwrap();
+        let frames: Vec<&str> = text.split("\n\n").collect();
+
+        // a header per move and the start, a frame per step and the start
+        assert_eq!(frames.len(), (moves.len() + 1) + (24 + 1));
+        assert!(text.starts_with("== Initial State ==\n\n......\n"));
+        assert_eq!(frames[3], "......\n......\n......\n......\n1H....");
+        assert!(text.ends_with(".1H##.\n....#.\ns###..\n"));
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 13);
@@ -151,18 +339,9 @@ U 20";
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day9.txt");
-        const ANSWERS: (usize, usize) = (5683, 2372);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(9);
         }
     }
 }
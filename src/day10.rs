@@ -1,9 +1,15 @@
+pub mod cpu;
+pub mod ocr;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     branch::alt, bytes::complete::tag, combinator::map, sequence::preceded, IResult, Parser,
 };
 
-use crate::common::nom::{nom_i64, nom_lines, process_input};
+use crate::{
+    common::nom::nom_i64,
+    parse::{try_process_lines, ParseError},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Instructions {
@@ -21,7 +27,27 @@ fn parse_instructions(s: &str) -> IResult<&str, Instructions> {
 
 #[aoc_generator(day10)]
 pub fn generator(input: &str) -> Vec<Instructions> {
-    process_input(nom_lines(parse_instructions))(input)
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<Instructions>, ParseError> {
+    try_process_lines(parse_instructions)(input)
+}
+
+impl From<&Instructions> for cpu::Instruction {
+    fn from(ins: &Instructions) -> Self {
+        match *ins {
+            Instructions::Noop => cpu::Instruction::new("noop", &[]),
+            Instructions::Addx(n) => cpu::Instruction::new("addx", &[n]),
+        }
+    }
+}
+
+/// Load `inputs` into a [`cpu::Cpu`] for stepping, tracing and breakpoints.
+pub fn load(inputs: &[Instructions]) -> cpu::Cpu {
+    let program = inputs.iter().map(cpu::Instruction::from).collect();
+    cpu::Cpu::new(cpu::InstructionTable::standard(), program)
+        .expect("the standard table runs every Instructions")
 }
 
 fn solve(inputs: &[Instructions], mut update: impl FnMut(i64, i64)) {
@@ -66,6 +92,16 @@ fn draw(screen: &mut [[u8; 40]; 6], cycle: i64, x: i64) {
 
 #[aoc(day10, part2)]
 pub fn part2(inputs: &[Instructions]) -> String {
+    try_part2(inputs).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// The letters on the screen, see [`part2_art`] for the screen itself.
+pub fn try_part2(inputs: &[Instructions]) -> Result<String, ocr::OcrError> {
+    ocr::read(&part2_art(inputs))
+}
+
+/// The screen as `#` and `.`, starting with a newline.
+pub fn part2_art(inputs: &[Instructions]) -> String {
     let mut screen = [[b'.'; 40]; 6];
 
     solve(inputs, |cycle, x| draw(&mut screen, cycle, x));
@@ -82,12 +118,12 @@ pub fn part2(inputs: &[Instructions]) -> String {
     }
 }
 
+pub const SAMPLE: &str = include_str!("day10/SAMPLE2.txt");
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE: &str = include_str!("day10/SAMPLE2.txt");
-
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE.trim_end_matches('\n')));
@@ -100,6 +136,19 @@ mod tests {
         assert_eq!(part1(&generator(SAMPLE.trim_end_matches('\n'))), 13140);
     }
 
+    #[test]
+    pub fn load_test() {
+        let inputs = generator(SAMPLE.trim_end_matches('\n'));
+        let mut fast = Vec::new();
+        solve(&inputs, |cycle, x| fast.push((cycle as usize, x)));
+
+        let ticks = load(&inputs)
+            .ticks()
+            .map(|t| (t.cycle, t.x))
+            .collect::<Vec<_>>();
+        assert_eq!(ticks, fast);
+    }
+
     #[test]
     pub fn part2_test() {
         const ANS: &str = r#"
@@ -110,31 +159,19 @@ mod tests {
 ######......######......######......####
 #######.......#######.......#######....."#;
 
-        assert_eq!(part2(&generator(SAMPLE.trim_end_matches('\n'))), ANS);
+        let inputs = generator(SAMPLE.trim_end_matches('\n'));
+        assert_eq!(part2_art(&inputs), ANS);
+        // The sample draws stripes, not letters
+        assert!(matches!(
+            try_part2(&inputs),
+            Err(ocr::OcrError::UnknownGlyph { column: 0, .. })
+        ));
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day10.txt");
-        const ANSWERS: (i64, &str) = (
-            13680,
-            r#"
-###..####..##..###..#..#.###..####.###..
-#..#....#.#..#.#..#.#.#..#..#.#....#..#.
-#..#...#..#....#..#.##...#..#.###..###..
-###...#...#.##.###..#.#..###..#....#..#.
-#....#....#..#.#....#.#..#....#....#..#.
-#....####..###.#....#..#.#....####.###.."#,
-        );
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(10);
         }
     }
 }
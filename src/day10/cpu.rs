@@ -0,0 +1,490 @@
+//! A small emulator for the handheld's CPU. Opcodes are looked up in an
+//! [`InstructionTable`], so new ones only need a new table entry.
+
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reg {
+    X,
+    Y,
+    Z,
+    W,
+}
+
+/// The register file. `X` starts at 1, the others at 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers([i64; 4]);
+
+impl Default for Registers {
+    fn default() -> Self {
+        Self([1, 0, 0, 0])
+    }
+}
+
+impl Index<Reg> for Registers {
+    type Output = i64;
+
+    fn index(&self, reg: Reg) -> &i64 {
+        &self.0[reg as usize]
+    }
+}
+
+impl IndexMut<Reg> for Registers {
+    fn index_mut(&mut self, reg: Reg) -> &mut i64 {
+        &mut self.0[reg as usize]
+    }
+}
+
+/// An opcode: how long it takes and what it does at the end of its last cycle.
+#[derive(Debug, Clone, Copy)]
+pub struct OpSpec {
+    pub mnemonic: &'static str,
+    pub cycles: usize,
+    pub arity: usize,
+    pub exec: fn(&mut Registers, &[i64]),
+}
+
+#[derive(Debug, Clone)]
+pub struct InstructionTable {
+    ops: Vec<OpSpec>,
+}
+
+impl Default for InstructionTable {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl InstructionTable {
+    /// A table with no opcodes at all.
+    pub fn empty() -> Self {
+        Self { ops: Vec::new() }
+    }
+
+    /// `noop` and `addx`.
+    pub fn standard() -> Self {
+        Self::empty()
+            .with(OpSpec {
+                mnemonic: "noop",
+                cycles: 1,
+                arity: 0,
+                exec: |_, _| {},
+            })
+            .with(OpSpec {
+                mnemonic: "addx",
+                cycles: 2,
+                arity: 1,
+                exec: |regs, args| regs[Reg::X] += args[0],
+            })
+    }
+
+    /// Add an opcode, replacing any with the same mnemonic.
+    pub fn with(mut self, spec: OpSpec) -> Self {
+        assert!(
+            spec.cycles > 0,
+            "{} must take at least a cycle",
+            spec.mnemonic
+        );
+        match self.position(spec.mnemonic) {
+            Some(i) => self.ops[i] = spec,
+            None => self.ops.push(spec),
+        }
+        self
+    }
+
+    fn position(&self, mnemonic: &str) -> Option<usize> {
+        self.ops.iter().position(|op| op.mnemonic == mnemonic)
+    }
+
+    pub fn get(&self, mnemonic: &str) -> Option<&OpSpec> {
+        self.position(mnemonic).map(|i| &self.ops[i])
+    }
+}
+
+/// A line of a program: a mnemonic and its integer arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub mnemonic: String,
+    pub args: Vec<i64>,
+}
+
+impl Instruction {
+    pub fn new(mnemonic: &str, args: &[i64]) -> Self {
+        Self {
+            mnemonic: mnemonic.to_string(),
+            args: args.to_vec(),
+        }
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let mnemonic = words.next().unwrap_or_default().to_string();
+        let args = words.map(str::parse).collect::<Result<_, _>>()?;
+
+        Ok(Self { mnemonic, args })
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.mnemonic)?;
+        for arg in &self.args {
+            write!(f, " {arg}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CpuError {
+    UnknownOpcode {
+        pc: usize,
+        mnemonic: String,
+    },
+    Arity {
+        pc: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpuError::UnknownOpcode { pc, mnemonic } => {
+                write!(f, "instruction {pc}: unknown opcode {mnemonic:?}")
+            }
+            CpuError::Arity {
+                pc,
+                expected,
+                found,
+            } => write!(
+                f,
+                "instruction {pc}: expected {expected} arguments, got {found}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CpuError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Stop before this cycle runs.
+    Cycle(usize),
+    /// Stop when the register changes to this value.
+    Register(Reg, i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    Halted,
+    Breakpoint(Breakpoint),
+}
+
+/// One cycle: its number, `X` during it and the instruction being run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tick {
+    pub cycle: usize,
+    pub x: i64,
+    pub pc: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cpu {
+    table: InstructionTable,
+    program: Vec<Instruction>,
+    /// Index in `table` of each instruction's opcode.
+    ops: Vec<usize>,
+    regs: Registers,
+    pc: usize,
+    /// The next cycle to run, from 1.
+    cycle: usize,
+    /// Cycles already spent on the current instruction.
+    elapsed: usize,
+    breakpoints: Vec<Breakpoint>,
+    /// The cycle [`Cpu::run`] last stopped before, so resuming goes past it.
+    stopped_at: Option<usize>,
+    /// The registers when [`Cpu::run`] last looked at them, so it also sees
+    /// changes made in between runs.
+    seen: Registers,
+    trace: Option<Vec<Tick>>,
+}
+
+impl Cpu {
+    /// Load `program`, checking every opcode and its arguments.
+    pub fn new(table: InstructionTable, program: Vec<Instruction>) -> Result<Self, CpuError> {
+        let ops = program
+            .iter()
+            .enumerate()
+            .map(|(pc, ins)| {
+                let op = table
+                    .position(&ins.mnemonic)
+                    .ok_or_else(|| CpuError::UnknownOpcode {
+                        pc,
+                        mnemonic: ins.mnemonic.clone(),
+                    })?;
+                let expected = table.ops[op].arity;
+                if ins.args.len() != expected {
+                    return Err(CpuError::Arity {
+                        pc,
+                        expected,
+                        found: ins.args.len(),
+                    });
+                }
+                Ok(op)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            table,
+            program,
+            ops,
+            regs: Registers::default(),
+            pc: 0,
+            cycle: 1,
+            elapsed: 0,
+            breakpoints: Vec::new(),
+            stopped_at: None,
+            seen: Registers::default(),
+            trace: None,
+        })
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.regs
+    }
+
+    pub fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.regs
+    }
+
+    pub fn x(&self) -> i64 {
+        self.regs[Reg::X]
+    }
+
+    /// Index of the instruction being run.
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The next cycle to run.
+    pub fn cycle(&self) -> usize {
+        self.cycle
+    }
+
+    pub fn halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint);
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Start recording every cycle from now on.
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    pub fn trace(&self) -> &[Tick] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// The trace as text, one `cycle X instruction` line per cycle.
+    pub fn trace_log(&self) -> String {
+        self.trace()
+            .iter()
+            .map(|t| format!("{:>4} {:>4} {}\n", t.cycle, t.x, self.program[t.pc]))
+            .collect()
+    }
+
+    /// Run one cycle, `None` once the program is over.
+    pub fn step(&mut self) -> Option<Tick> {
+        if self.halted() {
+            return None;
+        }
+
+        let tick = Tick {
+            cycle: self.cycle,
+            x: self.x(),
+            pc: self.pc,
+        };
+        if let Some(trace) = &mut self.trace {
+            trace.push(tick);
+        }
+
+        self.cycle += 1;
+        self.elapsed += 1;
+        let spec = &self.table.ops[self.ops[self.pc]];
+        if self.elapsed == spec.cycles {
+            (spec.exec)(&mut self.regs, &self.program[self.pc].args);
+            self.pc += 1;
+            self.elapsed = 0;
+        }
+
+        Some(tick)
+    }
+
+    /// [`Cpu::step`] until the program ends, ignoring breakpoints.
+    pub fn ticks(&mut self) -> impl Iterator<Item = Tick> + '_ {
+        std::iter::from_fn(|| self.step())
+    }
+
+    /// Run the rest of the current instruction, returning its cycles.
+    pub fn step_instruction(&mut self) -> Vec<Tick> {
+        let pc = self.pc;
+        let mut ticks = Vec::new();
+        while self.pc == pc {
+            match self.step() {
+                Some(tick) => ticks.push(tick),
+                None => break,
+            }
+        }
+        ticks
+    }
+
+    /// Run until a breakpoint hits or the program ends. Breakpoints are
+    /// checked before every cycle, including the first one, but a cycle
+    /// breakpoint does not hit again right where the previous run stopped.
+    /// Register changes made by stepping in between runs count too.
+    pub fn run(&mut self) -> Stop {
+        let resumed = self.stopped_at.take();
+
+        while !self.halted() {
+            let hit = self.breakpoints.iter().find(|bp| match **bp {
+                Breakpoint::Cycle(cycle) => self.cycle == cycle && resumed != Some(cycle),
+                Breakpoint::Register(reg, value) => {
+                    self.seen[reg] != value && self.regs[reg] == value
+                }
+            });
+            self.seen = self.regs;
+            if let Some(&bp) = hit {
+                self.stopped_at = Some(self.cycle);
+                return Stop::Breakpoint(bp);
+            }
+
+            self.step();
+        }
+        Stop::Halted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program(text: &str) -> Vec<Instruction> {
+        text.lines().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    pub fn step_test() {
+        let mut cpu = Cpu::new(
+            InstructionTable::standard(),
+            program("noop\naddx 3\naddx -5"),
+        )
+        .unwrap();
+        cpu.enable_trace();
+
+        let xs: Vec<_> = cpu.ticks().map(|t| (t.cycle, t.x)).collect();
+        assert_eq!(xs, [(1, 1), (2, 1), (3, 1), (4, 4), (5, 4)]);
+        assert_eq!(cpu.x(), -1);
+        assert!(cpu.halted());
+        assert_eq!(cpu.trace_log().lines().nth(2), Some("   3    1 addx 3"));
+    }
+
+    #[test]
+    pub fn breakpoint_test() {
+        let mut cpu = Cpu::new(
+            InstructionTable::standard(),
+            program("addx 2\naddx 3\nnoop\naddx -5\nnoop"),
+        )
+        .unwrap();
+        cpu.add_breakpoint(Breakpoint::Cycle(4));
+        cpu.add_breakpoint(Breakpoint::Register(Reg::X, 1));
+
+        assert_eq!(cpu.run(), Stop::Breakpoint(Breakpoint::Cycle(4)));
+        assert_eq!((cpu.cycle(), cpu.x(), cpu.pc()), (4, 3, 1));
+        assert_eq!(cpu.step_instruction().len(), 1);
+        assert_eq!(cpu.run(), Stop::Breakpoint(Breakpoint::Register(Reg::X, 1)));
+        assert_eq!(cpu.cycle(), 8);
+        assert_eq!(cpu.run(), Stop::Halted);
+    }
+
+    #[test]
+    pub fn first_cycle_breakpoint_test() {
+        let mut cpu = Cpu::new(InstructionTable::standard(), program("noop\naddx 2")).unwrap();
+        cpu.add_breakpoint(Breakpoint::Cycle(1));
+        cpu.add_breakpoint(Breakpoint::Register(Reg::X, 1));
+
+        assert_eq!(cpu.run(), Stop::Breakpoint(Breakpoint::Cycle(1)));
+        assert_eq!((cpu.cycle(), cpu.x(), cpu.pc()), (1, 1, 0));
+
+        // Resuming runs past the breakpoint, and X starting out at 1 is not a
+        // change to 1
+        assert_eq!(cpu.run(), Stop::Halted);
+        assert_eq!(cpu.x(), 3);
+    }
+
+    #[test]
+    pub fn changed_between_runs_test() {
+        let mut cpu = Cpu::new(InstructionTable::standard(), program("addx 2\nnoop")).unwrap();
+        cpu.add_breakpoint(Breakpoint::Register(Reg::X, 3));
+        cpu.add_breakpoint(Breakpoint::Register(Reg::Y, 5));
+
+        cpu.step_instruction();
+        assert_eq!(cpu.run(), Stop::Breakpoint(Breakpoint::Register(Reg::X, 3)));
+        assert_eq!(cpu.cycle(), 3);
+
+        cpu.registers_mut()[Reg::Y] = 5;
+        assert_eq!(cpu.run(), Stop::Breakpoint(Breakpoint::Register(Reg::Y, 5)));
+        assert_eq!(cpu.run(), Stop::Halted);
+    }
+
+    #[test]
+    pub fn custom_opcode_test() {
+        let table = InstructionTable::standard()
+            .with(OpSpec {
+                mnemonic: "mulx",
+                cycles: 3,
+                arity: 1,
+                exec: |regs, args| regs[Reg::X] *= args[0],
+            })
+            .with(OpSpec {
+                mnemonic: "movy",
+                cycles: 1,
+                arity: 0,
+                exec: |regs, _| regs[Reg::Y] = regs[Reg::X],
+            });
+
+        let mut cpu = Cpu::new(table.clone(), program("addx 4\nmulx 3\nmovy")).unwrap();
+        assert_eq!(cpu.ticks().count(), 6);
+        assert_eq!(cpu.registers()[Reg::Y], 15);
+
+        let err = Cpu::new(table.clone(), program("noop\ndivx 2")).unwrap_err();
+        assert_eq!(
+            err,
+            CpuError::UnknownOpcode {
+                pc: 1,
+                mnemonic: "divx".to_string()
+            }
+        );
+        let err = Cpu::new(table, program("mulx")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "instruction 0: expected 1 arguments, got 0"
+        );
+    }
+}
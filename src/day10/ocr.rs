@@ -0,0 +1,140 @@
+//! Reads the 4x6 block letters Advent of Code draws on its screens. `Y` is the
+//! odd one out: it is five columns wide and fills its blank column.
+
+use std::fmt;
+
+const HEIGHT: usize = 6;
+const WIDTH: usize = 4;
+/// Each glyph is followed by a blank column.
+const PITCH: usize = WIDTH + 1;
+
+#[rustfmt::skip]
+const FONT: [(char, [&str; HEIGHT]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OcrError {
+    /// The art is not six rows of the same width.
+    Shape,
+    /// No letter matches the glyph starting at `column` (0-based).
+    UnknownGlyph { column: usize, glyph: String },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OcrError::Shape => write!(f, "expected {HEIGHT} rows of the same width"),
+            OcrError::UnknownGlyph { column, glyph } => {
+                write!(f, "unrecognized glyph at column {column}:\n{glyph}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+/// Read the letters drawn with `#` and `.` in `art`. Blank lines around the
+/// art are ignored. Widths are in bytes, so any other character just fails to
+/// match.
+pub fn read(art: &str) -> Result<String, OcrError> {
+    let rows: Vec<&[u8]> = art.trim_matches('\n').lines().map(str::as_bytes).collect();
+    let width = rows.first().map_or(0, |row| row.len());
+    if rows.len() != HEIGHT || rows.iter().any(|row| row.len() != width) {
+        return Err(OcrError::Shape);
+    }
+
+    (0..width)
+        .step_by(PITCH)
+        .map(|column| {
+            let glyph = |len: usize| {
+                let end = (column + len).min(width);
+                rows.iter().map(move |row| &row[column..end])
+            };
+
+            FONT.iter()
+                .find(|(_, pattern)| glyph(pattern[0].len()).eq(pattern.map(str::as_bytes)))
+                .map(|&(letter, _)| letter)
+                .ok_or_else(|| OcrError::UnknownGlyph {
+                    column,
+                    glyph: String::from_utf8_lossy(&glyph(WIDTH).collect::<Vec<_>>().join(&b'\n'))
+                        .into_owned(),
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ART: &str = r"
+###..####..##..###..#..#.###..####.###..
+#..#....#.#..#.#..#.#.#..#..#.#....#..#.
+#..#...#..#....#..#.##...#..#.###..###..
+###...#...#.##.###..#.#..###..#....#..#.
+#....#....#..#.#....#.#..#....#....#..#.
+#....####..###.#....#..#.#....####.###..";
+
+    #[test]
+    pub fn read_test() {
+        assert_eq!(read(ART), Ok("PZGPKPEB".to_string()));
+    }
+
+    #[test]
+    pub fn wide_glyph_test() {
+        let art = r"
+#...#####
+#...##...
+.#.#.###.
+..#..#...
+..#..#...
+..#..#...";
+
+        assert_eq!(read(art), Ok("YF".to_string()));
+    }
+
+    #[test]
+    pub fn error_test() {
+        let art = ART.replace("#..#....#.#..#.#", "#..#....#.#..#.##");
+        let err = read(&art).unwrap_err();
+
+        assert!(matches!(err, OcrError::Shape));
+
+        let art = ART.replacen("###..####", "###..###.", 1);
+        let err = read(&art).unwrap_err();
+
+        assert_eq!(
+            err,
+            OcrError::UnknownGlyph {
+                column: 5,
+                glyph: "###.\n...#\n..#.\n.#..\n#...\n####".to_string()
+            }
+        );
+        let msg = err.to_string();
+        assert!(msg.starts_with("unrecognized glyph at column 5"));
+
+        // A glyph boundary in the middle of a multi-byte character
+        let art = ART.trim_start().lines().map(|row| format!("..█{row}"));
+        let err = read(&art.collect::<Vec<_>>().join("\n")).unwrap_err();
+
+        assert!(matches!(err, OcrError::UnknownGlyph { column: 0, .. }));
+    }
+}
@@ -1,6 +1,9 @@
-use crate::common::{
-    heap_retain,
-    nom::{nom_lines, nom_u64, nom_u8, nom_usize, process_input},
+use crate::{
+    common::{
+        heap_retain,
+        nom::{nom_lines, nom_u64, nom_u8, nom_usize},
+    },
+    parse::{try_process_input, ParseError},
 };
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
@@ -81,7 +84,11 @@ fn parse_monkey(s: &str) -> IResult<&str, Monkey> {
 
 #[aoc_generator(day11)]
 pub fn generator(input: &str) -> Vec<Monkey> {
-    process_input(nom_lines(parse_monkey))(input)
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    try_process_input(nom_lines(parse_monkey))(input)
 }
 
 fn solve<const ITERATIONS: usize>(
@@ -129,11 +136,7 @@ pub fn part2(monkeys: &[Monkey]) -> usize {
     )
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"Monkey 0:
+pub const SAMPLE: &str = r"Monkey 0:
   Starting items: 79, 98
   Operation: new = old * 19
   Test: divisible by 23
@@ -162,6 +165,10 @@ Monkey 3:
     If false: throw to monkey 1
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -180,18 +187,9 @@ Monkey 3:
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day11.txt");
-        const ANSWERS: (usize, usize) = (111210, 15447387620);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(11);
         }
     }
 }
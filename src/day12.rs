@@ -1,88 +1,84 @@
-use crate::common::{pathfinding::bfs_count_bitset, utils::neighbors};
+use crate::{common::pathfinding::bfs_count_bitset, grid::Grid, parse::ParseError};
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::error::ErrorKind;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct HeightMap {
-    map: Vec<Vec<u8>>,
+    map: Grid<u8>,
     start: (usize, usize),
     end: (usize, usize),
 }
 
 #[aoc_generator(day12)]
 pub fn generator(input: &str) -> HeightMap {
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<HeightMap, ParseError> {
     let mut start = (0, 0);
     let mut end = (0, 0);
-    let map: Vec<_> = input
-        .lines()
-        .enumerate()
-        .map(|(r, line)| {
-            line.bytes()
-                .enumerate()
-                .map(|(c, cell)| match cell {
-                    b'S' => {
-                        start = (r, c);
-                        b'a'
-                    }
-                    b'E' => {
-                        end = (r, c);
-                        b'z'
-                    }
-                    _ => cell,
-                })
-                .collect()
-        })
-        .collect();
-
-    HeightMap { map, start, end }
+    let map = Grid::try_parse(input, |pos, cell| match cell {
+        b'S' => {
+            start = pos;
+            Ok(b'a')
+        }
+        b'E' => {
+            end = pos;
+            Ok(b'z')
+        }
+        b'a'..=b'z' => Ok(cell),
+        _ => Err(ErrorKind::OneOf),
+    })?;
+
+    Ok(HeightMap { map, start, end })
 }
 
 #[aoc(day12, part1)]
 pub fn part1(inputs: &HeightMap) -> usize {
-    let r_max = inputs.map.len();
-    let c_max = inputs.map[0].len();
+    let map = &inputs.map;
 
     bfs_count_bitset(
         &inputs.start,
-        |state| {
-            let height = inputs.map[state.0][state.1];
+        |&state| {
+            let height = map[state];
 
-            neighbors(state.0, state.1, r_max, c_max)
-                .filter(move |(y, x)| height + 1 >= inputs.map[*y][*x])
+            map.neighbors(state)
+                .filter(move |&pos| height + 1 >= map[pos])
         },
         |state| state == &inputs.end,
-        |x| x.0 * c_max + x.1,
+        |&x| map.flat_index(x),
     )
     .unwrap()
 }
 
 #[aoc(day12, part2)]
 pub fn part2(inputs: &HeightMap) -> usize {
-    let r_max = inputs.map.len();
-    let c_max = inputs.map[0].len();
+    let map = &inputs.map;
+
     bfs_count_bitset(
         &inputs.end,
-        |state| {
-            let height = inputs.map[state.0][state.1];
+        |&state| {
+            let height = map[state];
 
-            neighbors(state.0, state.1, r_max, c_max)
-                .filter(move |(y, x)| inputs.map[*y][*x] + 1 >= height)
+            map.neighbors(state)
+                .filter(move |&pos| map[pos] + 1 >= height)
         },
-        |state| inputs.map[state.0][state.1] == b'a',
-        |x| x.0 * c_max + x.1,
+        |&state| map[state] == b'a',
+        |&x| map.flat_index(x),
     )
     .unwrap()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"Sabqponm
+pub const SAMPLE: &str = r"Sabqponm
 abcryxxl
 accszExk
 acctuvwj
 abdefghi";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -101,18 +97,9 @@ abdefghi";
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day12.txt");
-        const ANSWERS: (usize, usize) = (423, 416);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(12);
         }
     }
 }
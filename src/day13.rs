@@ -8,7 +8,10 @@ use nom::{
     IResult, Parser,
 };
 
-use crate::common::nom::{nom_u8, process_input};
+use crate::{
+    common::nom::nom_u8,
+    parse::{try_process_input, ParseError},
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Signal {
@@ -51,7 +54,11 @@ fn signals(s: &str) -> IResult<&str, [Signal; 2]> {
 
 #[aoc_generator(day13)]
 pub fn generator(inputs: &str) -> Vec<[Signal; 2]> {
-    process_input(separated_list0(tag("\n\n"), signals))(inputs)
+    try_generator(inputs).unwrap()
+}
+
+pub fn try_generator(inputs: &str) -> Result<Vec<[Signal; 2]>, ParseError> {
+    try_process_input(separated_list0(tag("\n\n"), signals))(inputs)
 }
 
 #[aoc(day13, part1)]
@@ -87,11 +94,7 @@ pub fn part2(input: &[[Signal; 2]]) -> usize {
     x * y
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"[1,1,3,1,1]
+pub const SAMPLE: &str = r"[1,1,3,1,1]
 [1,1,5,1,1]
 
 [[1],[2,3,4]]
@@ -115,6 +118,10 @@ mod tests {
 [1,[2,[3,[4,[5,6,7]]]],8,9]
 [1,[2,[3,[4,[5,6,0]]]],8,9]";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         // println!("{:?}", generator1(SAMPLE));
@@ -133,18 +140,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day13.txt");
-        const ANSWERS: (usize, usize) = (5852, 24190);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(13);
         }
     }
 }
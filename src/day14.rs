@@ -5,9 +5,16 @@ use nom::{
     bytes::complete::tag, character::streaming::char, multi::separated_list1,
     sequence::separated_pair,
 };
-use rustc_hash::FxHashMap as HashMap;
 
-use crate::common::nom::{nom_lines, nom_usize, process_input};
+use crate::{
+    common::nom::nom_usize,
+    grid::Grid,
+    parse::{try_process_lines, ParseError},
+};
+
+const AIR: u8 = b'.';
+const ROCK: u8 = b'#';
+const SAND: u8 = b'o';
 
 fn mk_range_inc(a: usize, b: usize) -> RangeInclusive<usize> {
     if a < b {
@@ -18,34 +25,48 @@ fn mk_range_inc(a: usize, b: usize) -> RangeInclusive<usize> {
 }
 
 #[aoc_generator(day14)]
-pub fn generator(input: &str) -> HashMap<(usize, usize), u8> {
-    let mut map = HashMap::default();
+pub fn generator(input: &str) -> Grid<u8> {
+    try_generator(input).unwrap()
+}
 
-    process_input(nom_lines(separated_list1(
+pub fn try_generator(input: &str) -> Result<Grid<u8>, ParseError> {
+    let lines = try_process_lines(separated_list1(
         tag(" -> "),
         separated_pair(nom_usize, char(','), nom_usize),
-    )))(input)
-    .into_iter()
-    .for_each(|line| {
+    ))(input)?;
+
+    // Leave room for the part 2 floor and the sand pile spreading over it
+    let max_x = lines.iter().flatten().map(|x| x.0).max().unwrap_or(500);
+    let max_y = lines.iter().flatten().map(|x| x.1).max().unwrap_or(0);
+    let mut map = Grid::new(max_x.max(500 + max_y + 3) + 2, max_y + 3, AIR);
+
+    lines.into_iter().for_each(|line| {
         line.windows(2).for_each(|x| {
             if x[0].0 == x[1].0 {
                 for y in mk_range_inc(x[0].1, x[1].1) {
-                    map.insert((x[0].0, y), b'#');
+                    map[(y, x[0].0)] = ROCK;
                 }
             } else {
                 for y in mk_range_inc(x[0].0, x[1].0) {
-                    map.insert((y, x[0].1), b'#');
+                    map[(x[0].1, y)] = ROCK;
                 }
             }
         })
     });
 
-    map
+    Ok(map)
+}
+
+fn lowest_rock(map: &Grid<u8>) -> usize {
+    (0..map.height())
+        .rev()
+        .find(|&y| map.row(y).contains(&ROCK))
+        .unwrap()
 }
 
 #[aoc(day14, part1)]
-pub fn part1(map: &HashMap<(usize, usize), u8>) -> usize {
-    let max = map.keys().map(|x| x.1).max().unwrap();
+pub fn part1(map: &Grid<u8>) -> usize {
+    let max = lowest_rock(map);
     let mut map = map.clone();
     let mut count = 0;
 
@@ -53,16 +74,24 @@ pub fn part1(map: &HashMap<(usize, usize), u8>) -> usize {
         let mut x = 500_usize;
 
         for y in 0..=max {
-            if let Some(x2) = [0, -1, 1]
-                .iter()
-                .map(|diff| x.wrapping_add_signed(*diff))
-                .find(|new| !map.contains_key(&(*new, y)))
-            {
-                x = x2;
-            } else {
-                map.insert((x, y - 1), b'o');
-                count += 1;
-                continue 'outer;
+            let free = [0, -1, 1].into_iter().find_map(|diff| {
+                let new = x.checked_add_signed(diff);
+                match new.and_then(|new| map.get((y, new))) {
+                    Some(&AIR) => Some(new),
+                    Some(_) => None,
+                    // Past the edge there is no rock left to stop the sand
+                    None => Some(None),
+                }
+            });
+
+            match free {
+                Some(Some(x2)) => x = x2,
+                Some(None) => break 'outer,
+                None => {
+                    map[(y - 1, x)] = SAND;
+                    count += 1;
+                    continue 'outer;
+                }
             }
         }
 
@@ -73,28 +102,43 @@ pub fn part1(map: &HashMap<(usize, usize), u8>) -> usize {
 }
 
 #[aoc(day14, part2)]
-pub fn part2(map: &HashMap<(usize, usize), u8>) -> usize {
-    let max = map.keys().map(|x| x.1).max().unwrap() + 2;
-    let mut map = map.clone();
+pub fn part2(map: &Grid<u8>) -> usize {
+    let max = lowest_rock(map) + 2;
     let mut count = 0;
 
+    // The pile spreads up to `max` columns either side of the source, which
+    // is more than the input's columns left of 500 for deep caves
+    let pad = (max + 1).saturating_sub(500);
+    let mut map = if pad == 0 {
+        map.clone()
+    } else {
+        let mut padded = Grid::new(map.width() + pad, map.height(), AIR);
+        for y in 0..map.height() {
+            padded.row_mut(y)[pad..].copy_from_slice(map.row(y));
+        }
+        padded
+    };
+
     'outer: loop {
-        let (mut x, mut y) = (500_usize, 0);
+        let (mut x, mut y) = (500 + pad, 0);
 
         'inner: loop {
             if y < max {
                 y += 1;
                 for x_diff in [0, -1, 1] {
-                    if !map.contains_key(&(x.wrapping_add_signed(x_diff), y)) {
-                        x = x.wrapping_add_signed(x_diff);
+                    if let Some(x2) = x
+                        .checked_add_signed(x_diff)
+                        .filter(|&x2| map.get((y, x2)) == Some(&AIR))
+                    {
+                        x = x2;
                         continue 'inner;
                     }
                 }
-                if map.get(&(x, y - 1)) == Some(&b'o') && y == 1 {
+                if map[(y - 1, x)] == SAND && y == 1 {
                     break 'outer;
                 }
             }
-            map.insert((x, y - 1), b'o');
+            map[(y - 1, x)] = SAND;
             count += 1;
             break;
         }
@@ -103,12 +147,20 @@ pub fn part2(map: &HashMap<(usize, usize), u8>) -> usize {
     count
 }
 
+pub const SAMPLE: &str = r"498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE: &str = r"498,4 -> 498,6 -> 496,6
-503,4 -> 502,4 -> 502,9 -> 494,9";
+    /// Rocks that make the sand slide down and left past column 0
+    fn channel() -> Grid<u8> {
+        let input = (1..=501)
+            .map(|y| format!("{},{y} -> {},{y}", 501 - y, 502 - y))
+            .collect::<Vec<_>>();
+        generator(&input.join("\n"))
+    }
 
     #[test]
     pub fn input_test() {
@@ -120,26 +172,23 @@ mod tests {
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 24);
+
+        // Sand sliding off column 0 falls into the abyss
+        assert_eq!(part1(&channel()), 0);
     }
 
     #[test]
     pub fn part2_test() {
         assert_eq!(part2(&generator(SAMPLE)), 93);
+
+        // The channel fills up, plus three grains spreading on the floor
+        assert_eq!(part2(&channel()), 502 + 3);
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day14.txt");
-        const ANSWERS: (usize, usize) = (644, 27324);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(14);
         }
     }
 }
@@ -6,7 +6,32 @@ use itertools::Itertools;
 use nom::{IResult, Parser, bytes::complete::tag, combinator::map};
 use rayon::prelude::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use crate::common::nom::{nom_i64, nom_lines, process_input};
+use crate::{
+    common::nom::nom_i64,
+    parse::{ParseError, try_process_lines},
+};
+
+/// The constants that differ between the sample and the real puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PuzzleParams {
+    /// Row counted in part 1.
+    pub row: i64,
+    /// Part 2 searches `0..=max` on both axes.
+    pub max: i64,
+}
+
+impl PuzzleParams {
+    pub const SAMPLE: Self = Self { row: 10, max: 20 };
+}
+
+impl Default for PuzzleParams {
+    fn default() -> Self {
+        Self {
+            row: 2000000,
+            max: 4000000,
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct SensorReport {
@@ -38,7 +63,11 @@ fn parse_sensor_report(line: &str) -> IResult<&str, SensorReport> {
 
 #[aoc_generator(day15)]
 pub fn generator(input: &str) -> Vec<SensorReport> {
-    process_input(nom_lines(parse_sensor_report))(input)
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Vec<SensorReport>, ParseError> {
+    try_process_lines(parse_sensor_report)(input)
 }
 
 fn manhattan_distance(a: (i64, i64), b: (i64, i64)) -> i64 {
@@ -47,6 +76,10 @@ fn manhattan_distance(a: (i64, i64), b: (i64, i64)) -> i64 {
 
 #[aoc(day15, part1)]
 pub fn part1(reports: &[SensorReport]) -> usize {
+    part1_with(reports, &PuzzleParams::default())
+}
+
+pub fn part1_with(reports: &[SensorReport], params: &PuzzleParams) -> usize {
     let objects = reports
         .iter()
         .flat_map(|&SensorReport { sensor, beacon, .. }| [sensor, beacon])
@@ -63,7 +96,7 @@ pub fn part1(reports: &[SensorReport]) -> usize {
         .into_option()
         .unwrap();
 
-    let y = if reports.len() == 14 { 10 } else { 2000000 };
+    let y = params.row;
 
     (beacon_min_max.0..=beacon_min_max.1)
         .into_par_iter()
@@ -78,19 +111,25 @@ pub fn part1(reports: &[SensorReport]) -> usize {
 
 #[aoc(day15, part2)]
 pub fn part2(reports: &[SensorReport]) -> i64 {
+    part2_with(reports, &PuzzleParams::default())
+}
+
+pub fn part2_with(reports: &[SensorReport], params: &PuzzleParams) -> i64 {
     let poi = reports
         .iter()
         .flat_map(|&SensorReport { sensor, beacon, .. }| [sensor, beacon])
         .collect();
 
-    let ans = scan(reports, &poi).expect("not found");
+    let ans = scan(reports, &poi, params.max).expect("not found");
 
     ans.1 * 4000000 + ans.0
 }
 
-fn scan(inputs: &[SensorReport], points_of_interest: &HashSet<(i64, i64)>) -> Option<(i64, i64)> {
-    let max = if inputs.len() == 14 { 20 } else { 4000000 };
-
+fn scan(
+    inputs: &[SensorReport],
+    points_of_interest: &HashSet<(i64, i64)>,
+    max: i64,
+) -> Option<(i64, i64)> {
     inputs.par_iter().find_map_any(|o| {
         let mut point = (o.sensor.0 - o.distance - 1, o.sensor.1);
 
@@ -125,11 +164,7 @@ fn out_range(
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+pub const SAMPLE: &str = r"Sensor at x=2, y=18: closest beacon is at x=-2, y=15
 Sensor at x=9, y=16: closest beacon is at x=10, y=16
 Sensor at x=13, y=2: closest beacon is at x=15, y=3
 Sensor at x=12, y=14: closest beacon is at x=10, y=16
@@ -144,6 +179,10 @@ Sensor at x=16, y=7: closest beacon is at x=15, y=3
 Sensor at x=14, y=3: closest beacon is at x=15, y=3
 Sensor at x=20, y=1: closest beacon is at x=15, y=3";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -153,27 +192,21 @@ Sensor at x=20, y=1: closest beacon is at x=15, y=3";
 
     #[test]
     pub fn part1_test() {
-        assert_eq!(part1(&generator(SAMPLE)), 26);
+        assert_eq!(part1_with(&generator(SAMPLE), &PuzzleParams::SAMPLE), 26);
     }
 
     #[test]
     pub fn part2_test() {
-        assert_eq!(part2(&generator(SAMPLE)), 56000011);
+        assert_eq!(
+            part2_with(&generator(SAMPLE), &PuzzleParams::SAMPLE),
+            56000011
+        );
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day15.txt");
-        const ANSWERS: (usize, i64) = (6275922, 11747175442119);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(15);
         }
     }
 }
@@ -8,10 +8,14 @@ use nom::{
     IResult, Parser,
     branch::alt,
     bytes::complete::{tag, take},
+    error::ErrorKind,
     multi::separated_list1,
 };
 
-use crate::common::nom::{nom_lines, nom_u32, process_input};
+use crate::{
+    common::nom::nom_u32,
+    parse::{ParseError, try_process_lines},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Valve<'a> {
@@ -141,16 +145,19 @@ fn calculate_distance(
 
 #[aoc_generator(day16)]
 pub fn generator(input: &str) -> Graph {
-    // Ensure that "AA" is always the first valve
-    fn move_start_to_front(mut valves: Vec<Valve<'_>>) -> Vec<Valve<'_>> {
-        let idx = valves.iter().position(|v| v.name == "AA").unwrap();
-        valves.swap(idx, 0);
-        valves
-    }
+    try_generator(input).unwrap()
+}
 
-    let valves = move_start_to_front(process_input(nom_lines(parse_input_value))(input));
+pub fn try_generator(input: &str) -> Result<Graph, ParseError> {
+    let mut valves = try_process_lines(parse_input_value)(input)?;
 
-    Graph::from_valves(&valves)
+    // Ensure that "AA" is always the first valve
+    let Some(idx) = valves.iter().position(|v| v.name == "AA") else {
+        return Err(ParseError::new(&input, &input, ErrorKind::Verify));
+    };
+    valves.swap(idx, 0);
+
+    Ok(Graph::from_valves(&valves))
 }
 
 fn dfs_part1(
@@ -272,11 +279,7 @@ pub fn part2(graph: &Graph) -> u32 {
         .unwrap()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+pub const SAMPLE: &str = r"Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
 Valve BB has flow rate=13; tunnels lead to valves CC, AA
 Valve CC has flow rate=2; tunnels lead to valves DD, BB
 Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
@@ -287,6 +290,10 @@ Valve HH has flow rate=22; tunnel leads to valve GG
 Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -305,18 +312,9 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day16.txt");
-        const ANSWERS: (u32, u32) = (1376, 1933);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(16);
         }
     }
 }
@@ -188,13 +188,13 @@ pub fn solve<const MAX_ITERATIONS: usize>(inputs: &[u8]) -> usize {
     max
 }
 
+pub const SAMPLE: &str = r">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::needless_borrow)]
     use super::*;
 
-    const SAMPLE: &str = r">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-
     fn generator(s: &str) -> &[u8] {
         s.as_bytes()
     }
@@ -217,18 +217,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day17.txt");
-        const ANSWERS: (usize, usize) = (3161, 1575931232076);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(17);
         }
     }
 }
@@ -3,9 +3,12 @@ use std::collections::VecDeque;
 use ahash::HashSet;
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::iproduct;
-use nom::{bytes::complete::tag, combinator::map, IResult, Parser};
+use nom::{bytes::complete::tag, combinator::map, IResult};
 
-use crate::common::nom::nom_i64;
+use crate::{
+    common::nom::nom_i64,
+    parse::{try_process_lines, ParseError},
+};
 // use rustc_hash::FxHashSet as HashSet;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
@@ -21,19 +24,17 @@ fn num(n: &str) -> IResult<&str, i64> {
 
 #[aoc_generator(day18)]
 pub fn generator(input: &str) -> HashSet<Cube> {
-    input
-        .lines()
-        .map(|line| {
-            map((num, tag(","), num, tag(","), num), |v| Cube {
-                x: v.0,
-                y: v.2,
-                z: v.4,
-            })
-            .parse(line)
-            .unwrap()
-            .1
-        })
-        .collect()
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<HashSet<Cube>, ParseError> {
+    let cubes = try_process_lines(map((num, tag(","), num, tag(","), num), |v| Cube {
+        x: v.0,
+        y: v.2,
+        z: v.4,
+    }))(input)?;
+
+    Ok(cubes.into_iter().collect())
 }
 
 fn adj(c: Cube) -> [Cube; 6] {
@@ -86,11 +87,7 @@ pub fn part2(inputs: &HashSet<Cube>) -> usize {
     solve(inputs) - solve(&air)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"2,2,2
+pub const SAMPLE: &str = r"2,2,2
 1,2,2
 3,2,2
 2,1,2
@@ -104,6 +101,10 @@ mod tests {
 2,1,5
 2,3,5";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -122,18 +123,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day18.txt");
-        const ANSWERS: (usize, usize) = (3494, 2062);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(18);
         }
     }
 }
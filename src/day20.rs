@@ -1,7 +1,9 @@
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::combinator::map;
 
-use crate::common::nom::{nom_i64, nom_lines, process_input};
+use crate::{
+    common::nom::nom_i64,
+    parse::{try_process_lines, ParseError},
+};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub struct Node {
@@ -17,17 +19,17 @@ impl std::fmt::Debug for Node {
 
 #[aoc_generator(day20)]
 pub fn generator(input: &str) -> Vec<Node> {
-    let mut pos = 0;
+    try_generator(input).unwrap()
+}
 
-    let x = process_input(nom_lines(map(nom_i64::<_, ()>, |val| {
-        let r = Node { pos, val };
-        pos += 1;
-        r
-    })))(input);
+pub fn try_generator(input: &str) -> Result<Vec<Node>, ParseError> {
+    let values = try_process_lines(nom_i64)(input)?;
 
-    // We need to bind and return to avoid `pos` incorrectly "borrowed value does not live long enough"
-    #[allow(clippy::let_and_return)]
-    x
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .map(|(pos, val)| Node { val, pos })
+        .collect())
 }
 
 fn solve<const ITERATIONS: usize>(inputs: &[Node]) -> i64 {
@@ -76,11 +78,7 @@ pub fn part2(inputs: &[Node]) -> i64 {
     solve::<10>(&data)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"1
+pub const SAMPLE: &str = r"1
 2
 -3
 3
@@ -88,6 +86,10 @@ mod tests {
 0
 4";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -106,18 +108,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day20.txt");
-        const ANSWERS: (i64, i64) = (7153, 6146976244822);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(20);
         }
     }
 }
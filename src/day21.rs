@@ -8,7 +8,10 @@ use nom::{
     IResult, Parser,
 };
 
-use crate::common::nom::{fold_separated_list0, nom_i64, process_input};
+use crate::{
+    common::nom::{fold_separated_list0, nom_i64},
+    parse::{try_process_input, ParseError},
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Operation {
@@ -125,7 +128,11 @@ fn solve_p2(name: &str, data: &HashMap<String, Operation>, target: i64) -> i64 {
 
 #[aoc_generator(day21)]
 pub fn generator(input: &str) -> HashMap<String, Operation> {
-    process_input(fold_separated_list0(
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<HashMap<String, Operation>, ParseError> {
+    try_process_input(fold_separated_list0(
         newline,
         map((parse_name, tag(": "), parse_op), |(name, _, operation)| {
             (name, operation)
@@ -165,11 +172,7 @@ pub fn part2(data: &HashMap<String, Operation>) -> i64 {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"root: pppw + sjmn
+pub const SAMPLE: &str = r"root: pppw + sjmn
 dbpl: 5
 cczh: sllz + lgvd
 zczc: 2
@@ -185,6 +188,10 @@ lgvd: ljgn * ptdq
 drzm: hmdt - zczc
 hmdt: 32";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -203,18 +210,9 @@ hmdt: 32";
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day21.txt");
-        const ANSWERS: (i64, i64) = (324122188240430, 3412650897405);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(21);
         }
     }
 }
@@ -6,11 +6,77 @@ use nom::{
     branch::alt,
     bytes::complete::tag,
     combinator::{complete, map},
+    error::ErrorKind,
     multi::many0,
     IResult, Parser,
 };
 
-use crate::common::nom::nom_u32;
+use crate::{
+    common::nom::nom_u32,
+    grid::Grid,
+    parse::{try_process_input, ParseError},
+};
+
+/// A side of a cube face in the net: the face's row and column, counted in
+/// faces, and the direction that walks off it.
+pub type Edge = (usize, usize, Dir);
+
+/// The constants that differ between the sample and the real puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PuzzleParams {
+    /// Edge length of a cube face.
+    pub face_size: usize,
+    /// The seven pairs of edges that are open in the net but meet once it is
+    /// folded into a cube.
+    pub seams: [(Edge, Edge); 7],
+}
+
+impl PuzzleParams {
+    /// The sample folds another net:
+    ///
+    /// ```text
+    ///   A
+    /// BCD
+    ///   EF
+    /// ```
+    pub const SAMPLE: Self = Self {
+        face_size: 4,
+        seams: [
+            ((0, 2, Dir::Up), (1, 0, Dir::Up)),
+            ((0, 2, Dir::Left), (1, 1, Dir::Up)),
+            ((0, 2, Dir::Right), (2, 3, Dir::Right)),
+            ((1, 0, Dir::Left), (2, 3, Dir::Down)),
+            ((1, 0, Dir::Down), (2, 2, Dir::Down)),
+            ((1, 1, Dir::Down), (2, 2, Dir::Left)),
+            ((1, 2, Dir::Right), (2, 3, Dir::Up)),
+        ],
+    };
+}
+
+impl Default for PuzzleParams {
+    /// All the real inputs share this net:
+    ///
+    /// ```text
+    ///  AB
+    ///  C
+    /// DE
+    /// F
+    /// ```
+    fn default() -> Self {
+        Self {
+            face_size: 50,
+            seams: [
+                ((0, 1, Dir::Up), (3, 0, Dir::Left)),
+                ((0, 1, Dir::Left), (2, 0, Dir::Left)),
+                ((0, 2, Dir::Up), (3, 0, Dir::Down)),
+                ((0, 2, Dir::Right), (2, 1, Dir::Right)),
+                ((0, 2, Dir::Down), (1, 1, Dir::Right)),
+                ((1, 1, Dir::Left), (2, 0, Dir::Up)),
+                ((2, 1, Dir::Down), (3, 0, Dir::Right)),
+            ],
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Eq)]
 enum Moves {
@@ -20,7 +86,7 @@ enum Moves {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Dir {
+pub enum Dir {
     Left,
     Right,
     Up,
@@ -48,6 +114,15 @@ impl Dir {
         }
     }
 
+    fn delta(&self) -> (isize, isize) {
+        match self {
+            Dir::Left => (0, -1),
+            Dir::Right => (0, 1),
+            Dir::Up => (-1, 0),
+            Dir::Down => (1, 0),
+        }
+    }
+
     fn turn(&self, m: &Moves) -> Self {
         match m {
             Moves::Forward(_) => panic!("can't turn a Forward"),
@@ -60,23 +135,19 @@ impl Dir {
 #[derive(Debug, PartialEq, Eq)]
 pub struct Input {
     mv: Vec<Moves>,
-    board: Vec<Vec<u8>>,
+    board: Grid<u8>,
 }
 
 impl Input {
     fn find_start(&self) -> (usize, usize) {
-        (0, self.board[0].iter().position(|&x| x == b'.').unwrap())
+        (
+            0,
+            self.board.row(0).iter().position(|&x| x == b'.').unwrap(),
+        )
     }
 
     fn board_get(&self, pos: (usize, usize)) -> Option<u8> {
-        let row = self.board.get(pos.0)?;
-        let cell = row.get(pos.1)?;
-
-        if *cell == b' ' {
-            None
-        } else {
-            Some(*cell)
-        }
+        self.board.get(pos).copied().filter(|&cell| cell != b' ')
     }
 }
 
@@ -91,12 +162,30 @@ fn parse_moves(line: &str) -> IResult<&str, Vec<Moves>> {
 
 #[aoc_generator(day22)]
 pub fn generator(input: &str) -> Input {
-    let mut iter = input.lines().rev();
-    let mv = parse_moves(iter.next().unwrap()).unwrap().1;
-    iter.next().unwrap();
-    let board = iter.rev().map(|l| l.to_string().into_bytes()).collect();
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<Input, ParseError> {
+    let Some((board, moves)) = input.trim_end().rsplit_once("\n\n") else {
+        // the board and the moves must be separated by a blank line
+        let end = &input[input.len()..];
+        return Err(ParseError::new(&input, &end, ErrorKind::Tag));
+    };
+    let mv = try_process_input(parse_moves)(moves)
+        .map_err(|e| e.with_line_offset(board.lines().count() + 1))?;
 
-    Input { mv, board }
+    for line in board.lines() {
+        if let Some(c) = line.bytes().position(|b| !b" .#".contains(&b)) {
+            return Err(ParseError::new(&input, &&line[c..], ErrorKind::OneOf));
+        }
+    }
+    if !board.lines().next().unwrap_or_default().contains('.') {
+        // the start is the first open tile of the top row
+        return Err(ParseError::new(&input, &board, ErrorKind::Verify));
+    }
+
+    let board = Grid::from_rows(board.lines().map(|l| l.as_bytes().to_vec()).collect(), b' ');
+    Ok(Input { mv, board })
 }
 
 fn solve<F: Fn(&Input, &mut Dir, u32, (usize, usize)) -> (usize, usize)>(
@@ -134,15 +223,21 @@ pub fn part1(inputs: &Input) -> usize {
 
 #[aoc(day22, part2)]
 pub fn part2(inputs: &Input) -> usize {
-    solve(inputs, part2::move_forward)
+    part2_with(inputs, &PuzzleParams::default())
 }
 
+pub fn part2_with(inputs: &Input, params: &PuzzleParams) -> usize {
+    solve(inputs, |input, direction, n, pos| {
+        part2::move_forward(input, direction, n, pos, params)
+    })
+}
+
+pub const SAMPLE: &str = include_str!("day22/SAMPLE.txt");
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE: &str = include_str!("day22/SAMPLE.txt");
-
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -150,29 +245,62 @@ mod tests {
         // assert_eq!(generator(SAMPLE), Object());
     }
 
+    #[test]
+    pub fn try_generator_test() {
+        let err = try_generator(&SAMPLE.replace("10R5", "10X5")).unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected),
+            (14, 3, ErrorKind::Eof)
+        );
+
+        let err = try_generator(&SAMPLE.replacen("#", "x", 1)).unwrap_err();
+        assert_eq!((err.line, err.expected), (1, ErrorKind::OneOf));
+
+        assert!(try_generator("  #\n\n1").is_err());
+    }
+
     #[test]
     pub fn part1_test() {
         assert_eq!(part1(&generator(SAMPLE)), 6032);
+
+        // Stepping off the first column or row wraps around the board, and
+        // stepping back onto it must not skip over it
+        let board = "....\n....\n";
+        assert_eq!(part1(&generator(&format!("{board}\n1LL1"))), 1000 + 4 + 2);
+        assert_eq!(part1(&generator(&format!("{board}\nR1LL1"))), 1000 + 4 + 3);
+        assert_eq!(part1(&generator(&format!("{board}\nLL1"))), 1000 + 16 + 2);
     }
 
     #[test]
     pub fn part2_test() {
-        // assert_eq!(part2(&generator(SAMPLE)), 5031);
-    }
+        assert_eq!(part2_with(&generator(SAMPLE), &PuzzleParams::SAMPLE), 5031);
 
-    mod regression {
-        use super::*;
+        // An open cube folded like the real inputs: going 4 faces straight
+        // ahead in any direction leads back to the start
+        let board = "    ........\n".repeat(4)
+            + &"    ....\n".repeat(4)
+            + &"........\n".repeat(4)
+            + &"....\n".repeat(4);
+        let params = PuzzleParams {
+            face_size: 4,
+            ..PuzzleParams::default()
+        };
 
-        const INPUT: &str = include_str!("../input/2022/day22.txt");
-        const ANSWERS: (usize, usize) = (26558, 110400);
+        for (moves, facing) in [("16", 0), ("R16", 1), ("LL16", 2), ("L16", 3)] {
+            let input = generator(&format!("{board}\n{moves}"));
+            assert_eq!(part2_with(&input, &params), 1000 + 5 * 4 + facing);
+        }
 
+        // Walking down off the bottom edge of the middle column (y == 3 * n)
+        // lands on the right edge of the bottom face, facing left
+        let input = generator(&format!("{board}\nR12"));
+        assert_eq!(part2_with(&input, &params), 13 * 1000 + 4 * 4 + 2);
+    }
+
+    mod regression {
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(22);
         }
     }
 }
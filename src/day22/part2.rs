@@ -1,80 +1,48 @@
-use super::{Dir, Input};
+use super::{Dir, Input, PuzzleParams};
 
 impl Dir {
-    fn forward_checked(&self, (y, x): (usize, usize)) -> (Option<usize>, Option<usize>) {
+    /// How far along this side of an `n` wide face `(r, c)` is, going
+    /// clockwise around the face.
+    fn edge_index(&self, (r, c): (usize, usize), n: usize) -> usize {
         match self {
-            Dir::Left => (Some(y), x.checked_sub(1)),
-            Dir::Right => (Some(y), Some(x + 1)),
-            Dir::Up => (y.checked_sub(1), Some(x)),
-            Dir::Down => (Some(y + 1), Some(x)),
+            Dir::Up => c,
+            Dir::Right => r,
+            Dir::Down => n - 1 - c,
+            Dir::Left => n - 1 - r,
         }
     }
-}
-
-fn wrap_y(direction: &mut Dir, pos: (Option<usize>, Option<usize>)) -> (usize, usize) {
-    let (y, x) = (pos.0.unwrap_or_default(), pos.1.unwrap_or_default());
 
-    match x {
-        0..=49 if y < 100 => {
-            *direction = direction.increment(1);
-            (x + 50, 50)
-        }
-        0..=49 if y >= 200 => (0, x + 100),
-        50..=99 if pos.0.is_none() => {
-            *direction = direction.increment(1);
-            (x + 100, 0)
-        }
-        50..=99 if y > 150 => {
-            *direction = direction.increment(1);
-            (x + 100, 49)
-        }
-        100..=149 if pos.0.is_none() => (199, x - 100),
-        100..=149 if y >= 50 => {
-            *direction = direction.increment(1);
-            (x - 50, 99)
+    /// Inverse of [`Dir::edge_index`].
+    fn edge_pos(&self, i: usize, n: usize) -> (usize, usize) {
+        match self {
+            Dir::Up => (0, i),
+            Dir::Right => (i, n - 1),
+            Dir::Down => (n - 1, n - 1 - i),
+            Dir::Left => (n - 1 - i, 0),
         }
-        _ => (y, x),
     }
 }
 
-fn wrap_x(direction: &mut Dir, pos: (Option<usize>, Option<usize>)) -> (usize, usize) {
-    let (y, x) = (pos.0.unwrap_or_default(), pos.1.unwrap_or_default());
+/// Walk off the net at `pos` onto the face it is glued to once folded.
+fn wrap(params: &PuzzleParams, (y, x): (usize, usize), direction: Dir) -> ((usize, usize), Dir) {
+    let n = params.face_size;
+    let edge = (y / n, x / n, direction);
+    let (row, column, side) = params
+        .seams
+        .iter()
+        .find_map(|&(a, b)| match edge {
+            _ if edge == a => Some(b),
+            _ if edge == b => Some(a),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("no seam for {edge:?}"));
 
-    match y {
-        0..=49 if x >= 150 => {
-            *direction = direction.increment(2);
-            (149 - y, 99)
-        }
-        0..=49 if x < 50 => {
-            *direction = direction.increment(2);
-            (149 - y, 0)
-        }
-        50..=99 if x >= 100 => {
-            *direction = direction.increment(3);
-            (49, y + 50)
-        }
-        50..=99 if x < 50 => {
-            *direction = direction.increment(3);
-            (100, y - 50)
-        }
-        100..=149 if x >= 100 => {
-            *direction = direction.increment(2);
-            (149 - y, 149)
-        }
-        100..=149 if pos.1.is_none() => {
-            *direction = direction.increment(2);
-            (149 - y, 50)
-        }
-        150..=199 if pos.1.is_none() => {
-            *direction = direction.increment(3);
-            (0, y - 100)
-        }
-        150..=199 if x >= 50 => {
-            *direction = direction.increment(3);
-            (149, y - 100)
-        }
-        _ => (y, x),
-    }
+    // Both faces go clockwise around themselves, so they run along the seam in
+    // opposite directions
+    let i = direction.edge_index((y % n, x % n), n);
+    let (r, c) = side.edge_pos(n - 1 - i, n);
+
+    ((row * n + r, column * n + c), side.increment(2))
 }
 
 pub(super) fn move_forward(
@@ -82,23 +50,24 @@ pub(super) fn move_forward(
     direction: &mut Dir,
     n: u32,
     mut pos: (usize, usize),
+    params: &PuzzleParams,
 ) -> (usize, usize) {
     for _ in 0..n {
-        let new_pos = direction.forward_checked(pos);
-        let mut new_direction = *direction;
-
-        let new_pos = match direction {
-            Dir::Up | Dir::Down => wrap_y(&mut new_direction, new_pos),
-            Dir::Left | Dir::Right => wrap_x(&mut new_direction, new_pos),
+        let (dy, dx) = direction.delta();
+        let ahead = pos
+            .0
+            .checked_add_signed(dy)
+            .zip(pos.1.checked_add_signed(dx));
+        let (new_pos, new_direction) = match ahead.filter(|&p| input.board_get(p).is_some()) {
+            Some(new_pos) => (new_pos, *direction),
+            None => wrap(params, pos, *direction),
         };
 
-        match input.board_get(new_pos) {
-            Some(b'#') | None => {}
-            Some(_) => {
-                pos = new_pos;
-                *direction = new_direction;
-            }
+        if input.board_get(new_pos) == Some(b'#') {
+            break;
         }
+        pos = new_pos;
+        *direction = new_direction;
     }
 
     pos
@@ -1,8 +1,11 @@
 use ahash::HashMapExt;
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
+use nom::error::ErrorKind;
 use rustc_hash::FxHashMap as HashMap;
 
+use crate::{grid::Grid, parse::ParseError};
+
 const ALL: [(i32, i32); 8] = [
     (-1, -1),
     (-1, 0),
@@ -27,7 +30,7 @@ const OFFSET: usize = WIDTH / 2;
 pub struct Elf(usize, usize);
 
 impl Elf {
-    fn tick<A: AsRef<[u8]>>(&self, map: &[A], i: usize) -> Option<Elf> {
+    fn tick(&self, map: &Grid<u8>, i: usize) -> Option<Elf> {
         if self.check(map, &ALL) {
             return None;
         }
@@ -54,10 +57,10 @@ impl Elf {
         })
     }
 
-    fn check<A: AsRef<[u8]>>(&self, map: &[A], dirs: &[(i32, i32)]) -> bool {
+    fn check(&self, map: &Grid<u8>, dirs: &[(i32, i32)]) -> bool {
         dirs.iter()
             .map(|delta| self.apply_delta(*delta))
-            .all(|Elf(r, c)| map[r].as_ref()[c] == EMPTY)
+            .all(|Elf(r, c)| map[(r, c)] == EMPTY)
     }
 
     fn apply_delta(&self, delta: (i32, i32)) -> Self {
@@ -69,27 +72,38 @@ impl Elf {
 }
 
 #[aoc_generator(day23)]
-pub fn generator(input: &str) -> ([[u8; WIDTH]; WIDTH], Vec<Elf>) {
-    let mut map = [[EMPTY; WIDTH]; WIDTH];
+pub fn generator(input: &str) -> (Grid<u8>, Vec<Elf>) {
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<(Grid<u8>, Vec<Elf>), ParseError> {
+    let mut map = Grid::new(WIDTH, WIDTH, EMPTY);
     let mut elves = Vec::new();
-    for (r_idx, row) in input.lines().enumerate() {
-        for (c_idx, cell) in row.bytes().enumerate() {
-            if cell == ELF {
-                map[r_idx + OFFSET][c_idx + OFFSET] = ELF;
+    Grid::try_parse(input, |(r_idx, c_idx), cell| {
+        // the elves spread out from the middle of the map
+        if r_idx >= WIDTH - OFFSET || c_idx >= WIDTH - OFFSET {
+            return Err(ErrorKind::Verify);
+        }
+        match cell {
+            ELF => {
+                map[(r_idx + OFFSET, c_idx + OFFSET)] = ELF;
                 elves.push(Elf(r_idx + OFFSET, c_idx + OFFSET));
             }
+            EMPTY => {}
+            _ => return Err(ErrorKind::OneOf),
         }
-    }
+        Ok(())
+    })?;
 
-    (map, elves)
+    Ok((map, elves))
 }
 
 fn solve<const ROUNDS: usize, N>(
-    a: &[[u8; WIDTH]; WIDTH],
+    a: &Grid<u8>,
     elves: &[Elf],
     ret: impl Fn(usize, &[Elf]) -> N,
 ) -> N {
-    let mut a = *a;
+    let mut a = a.clone();
     let mut elves = elves.to_vec();
 
     for round in 0..ROUNDS {
@@ -109,9 +123,9 @@ fn solve<const ROUNDS: usize, N>(
 
         for (new_pos, idx) in new_moves {
             if let Some(idx) = idx {
-                a[elves[idx].0][elves[idx].1] = EMPTY;
+                a[(elves[idx].0, elves[idx].1)] = EMPTY;
                 elves[idx] = new_pos;
-                a[elves[idx].0][elves[idx].1] = ELF;
+                a[(elves[idx].0, elves[idx].1)] = ELF;
             }
         }
     }
@@ -120,7 +134,7 @@ fn solve<const ROUNDS: usize, N>(
 }
 
 #[aoc(day23, part1)]
-pub fn part1((map, elves): &([[u8; WIDTH]; WIDTH], Vec<Elf>)) -> usize {
+pub fn part1((map, elves): &(Grid<u8>, Vec<Elf>)) -> usize {
     solve::<10, _>(map, elves, |_, a| {
         let (y_min, y_max) = a.iter().map(|Elf(y, _)| *y).minmax().into_option().unwrap();
         let (x_min, x_max) = a.iter().map(|Elf(_, x)| *x).minmax().into_option().unwrap();
@@ -130,15 +144,11 @@ pub fn part1((map, elves): &([[u8; WIDTH]; WIDTH], Vec<Elf>)) -> usize {
 }
 
 #[aoc(day23, part2)]
-pub fn part2((map, elves): &([[u8; WIDTH]; WIDTH], Vec<Elf>)) -> usize {
+pub fn part2((map, elves): &(Grid<u8>, Vec<Elf>)) -> usize {
     solve::<1024, _>(map, elves, |round, _| round + 1)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"....#..
+pub const SAMPLE: &str = r"....#..
 ..###.#
 #...#.#
 .#...##
@@ -146,6 +156,10 @@ mod tests {
 ##.#.##
 .#..#..";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     const SAMPLE_SMALL: &str = r".....
 ..##.
 ..#..
@@ -172,18 +186,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day23.txt");
-        const ANSWERS: (usize, usize) = (3864, 946);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(23);
         }
     }
 }
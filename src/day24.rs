@@ -1,9 +1,10 @@
 use std::fmt::Debug;
 
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::error::ErrorKind;
 use pathfinding::prelude::astar;
 
-use crate::common::utils::neighbors_and_self;
+use crate::{grid::Grid, parse::ParseError};
 
 const NONE: u8 = 0;
 const UP: u8 = 1 << 0;
@@ -20,7 +21,7 @@ fn square2value(sq: u8) -> Option<u8> {
         b'<' => LEFT,
         b'>' => RIGHT,
         b'#' => WALL,
-        _ => panic!("unknown character"),
+        _ => return None,
     })
 }
 
@@ -49,56 +50,48 @@ fn wrap_math(pos: usize, max: usize, positive: bool) -> usize {
 
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct WorldState {
-    world: Vec<Vec<u8>>,
+    world: Grid<u8>,
 }
 
 impl Debug for WorldState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f)?;
-        for line in self.world.iter() {
-            for cell in line.iter() {
-                write!(f, "{}", value2square(*cell))?;
-            }
-            writeln!(f)?;
-        }
-
-        Ok(())
+        writeln!(f, "{}", self.world.map(|&cell| value2square(cell)))
     }
 }
 
 impl WorldState {
     fn tick(&self) -> Self {
-        let height = self.world.len();
-        let width = self.world[0].len();
+        let height = self.world.height();
+        let width = self.world.width();
 
-        let mut world = vec![vec![NONE; width]; height];
+        let mut world = Grid::new(width, height, NONE);
 
-        for (ridx, row) in self.world.iter().enumerate() {
-            for (cidx, cell) in row.iter().enumerate() {
-                if cell == &NONE {
-                    continue;
-                }
+        for (ridx, cidx) in self.world.positions() {
+            let cell = self.world[(ridx, cidx)];
+            if cell == NONE {
+                continue;
+            }
 
-                if cell == &WALL {
-                    world[ridx][cidx] = WALL;
-                    continue;
-                }
+            if cell == WALL {
+                world[(ridx, cidx)] = WALL;
+                continue;
+            }
 
-                if cell & UP == UP {
-                    world[wrap_math(ridx, height, false)][cidx] |= UP;
-                }
+            if cell & UP == UP {
+                world[(wrap_math(ridx, height, false), cidx)] |= UP;
+            }
 
-                if cell & DOWN == DOWN {
-                    world[wrap_math(ridx, height, true)][cidx] |= DOWN;
-                }
+            if cell & DOWN == DOWN {
+                world[(wrap_math(ridx, height, true), cidx)] |= DOWN;
+            }
 
-                if cell & LEFT == LEFT {
-                    world[ridx][wrap_math(cidx, width, false)] |= LEFT;
-                }
+            if cell & LEFT == LEFT {
+                world[(ridx, wrap_math(cidx, width, false))] |= LEFT;
+            }
 
-                if cell & RIGHT == RIGHT {
-                    world[ridx][wrap_math(cidx, width, true)] |= RIGHT;
-                }
+            if cell & RIGHT == RIGHT {
+                world[(ridx, wrap_math(cidx, width, true))] |= RIGHT;
             }
         }
 
@@ -133,21 +126,19 @@ struct State {
 
 #[aoc_generator(day24)]
 pub fn generator(input: &str) -> World {
-    let mut res = Vec::<Vec<u8>>::new();
+    try_generator(input).unwrap()
+}
 
-    for line in input.lines() {
-        if !line.is_empty() {
-            res.push(line.bytes().filter_map(square2value).collect());
-        }
-    }
-    let height = res.len();
-    let width = res[0].len();
+pub fn try_generator(input: &str) -> Result<World, ParseError> {
+    let res = Grid::try_parse(input, |_, sq| square2value(sq).ok_or(ErrorKind::OneOf))?;
+    let height = res.height();
+    let width = res.width();
 
-    World {
+    Ok(World {
         states: vec![WorldState { world: res }],
         height,
         width,
-    }
+    })
 }
 
 fn solve(
@@ -156,8 +147,6 @@ fn solve(
     start: (usize, usize),
     end: (usize, usize),
 ) -> usize {
-    let height = worlds.height;
-    let width = worlds.width;
     let res = astar(
         &State {
             pos: start,
@@ -165,9 +154,10 @@ fn solve(
         },
         |state| {
             let world_num = state.world_num + 1;
-            let (r, c) = state.pos;
-            neighbors_and_self(r, c, height, width)
-                .filter(|&(y, x)| worlds.get(world_num).world[y][x] == NONE)
+            let world = &worlds.get(world_num).world;
+            world
+                .neighbors_and_self(state.pos)
+                .filter(|&pos| world[pos] == NONE)
                 .map(|pos| (State { pos, world_num }, 1))
                 .collect::<Vec<_>>()
         },
@@ -198,17 +188,17 @@ pub fn part2(world: &World) -> usize {
     solve(&mut world, move_num, start, end)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"#.######
+pub const SAMPLE: &str = r"#.######
 #>>.<^<#
 #.<..<<#
 #>v.><>#
 #<^v^^>#
 ######.#";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         // println!("{:?}", generator(SAMPLE));
@@ -227,18 +217,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day24.txt");
-        const ANSWERS: (usize, usize) = (271, 813);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(part1(&output), ANSWERS.0);
-            assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(24);
         }
     }
 }
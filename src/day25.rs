@@ -1,4 +1,7 @@
 use aoc_runner_derive::{aoc, aoc_generator};
+use nom::{bytes::complete::take_while1, combinator::map};
+
+use crate::parse::{try_process_lines, ParseError};
 
 fn to_dec(n: u8) -> i64 {
     match n {
@@ -21,7 +24,15 @@ fn snafu2decimal(s: &[u8]) -> i64 {
 
 #[aoc_generator(day25)]
 pub fn generator(input: &str) -> i64 {
-    input.lines().map(|l| snafu2decimal(l.as_bytes())).sum()
+    try_generator(input).unwrap()
+}
+
+pub fn try_generator(input: &str) -> Result<i64, ParseError> {
+    let numbers = try_process_lines(map(take_while1(|c| "012-=".contains(c)), |l: &str| {
+        snafu2decimal(l.as_bytes())
+    }))(input)?;
+
+    Ok(numbers.into_iter().sum())
 }
 
 #[aoc(day25, part1)]
@@ -48,11 +59,7 @@ pub fn part1(original: &i64) -> String {
     unsafe { String::from_utf8_unchecked(s) }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const SAMPLE: &str = r"1=-0-2
+pub const SAMPLE: &str = r"1=-0-2
 12111
 2=0=
 21
@@ -67,6 +74,10 @@ mod tests {
 122
 ";
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     pub fn input_test() {
         // println!("{:?}", generator(SAMPLE));
@@ -112,18 +123,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/day25.txt");
-        const ANSWERS: &str = "20-=0=02=-21=00-02=2";
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            let output = generator(input);
-
-            assert_eq!(output, 37067035390042);
-            assert_eq!(part1(&output), ANSWERS);
+            crate::answers::check(25);
         }
     }
 }
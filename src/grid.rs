@@ -0,0 +1,383 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use nom::error::ErrorKind;
+
+use crate::parse::ParseError;
+
+/// A `(row, column)` position.
+pub type Pos = (usize, usize);
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const ALL: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid stored row-major, indexed by `(row, column)`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Wrap row-major `cells`, which must hold exactly `width * height` values.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "grid is not {width}x{height}");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Build from ragged rows, padding the short ones with `fill`.
+    pub fn from_rows(rows: Vec<Vec<T>>, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, fill.clone());
+            cells.append(&mut row);
+        }
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parse one row per line, converting each byte with `f`, which gets the
+    /// byte's position and may reject it. All lines must have the same length.
+    pub fn try_parse(
+        input: &str,
+        mut f: impl FnMut(Pos, u8) -> Result<T, ErrorKind>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (r, line) in input.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            let width = *width.get_or_insert(line.len());
+            if line.len() != width {
+                return Err(ParseError::new(
+                    &input,
+                    &&line[width.min(line.len())..],
+                    ErrorKind::Verify,
+                ));
+            }
+            for (c, b) in line.bytes().enumerate() {
+                cells.push(f((r, c), b).map_err(|e| ParseError::new(&input, &&line[c..], e))?);
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (r, c): Pos) -> bool {
+        r < self.height && c < self.width
+    }
+
+    /// Offset of `pos` in the row-major storage, for bitsets and flat arrays.
+    pub fn flat_index(&self, (r, c): Pos) -> usize {
+        r * self.width + c
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[self.flat_index(pos)])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            let i = self.flat_index(pos);
+            Some(&mut self.cells[i])
+        } else {
+            None
+        }
+    }
+
+    /// Access with both coordinates taken modulo the grid size, so `(-1, -1)`
+    /// is the bottom right cell. Panics on an empty grid, like [`Grid::wrap`].
+    pub fn get_wrapping(&self, r: isize, c: isize) -> &T {
+        &self[self.wrap(r, c)]
+    }
+
+    /// Both coordinates taken modulo the grid size.
+    ///
+    /// # Panics
+    ///
+    /// When the grid is empty, as there is no cell to wrap around to. The
+    /// other wrapping methods go through here.
+    pub fn wrap(&self, r: isize, c: isize) -> Pos {
+        assert!(
+            self.width > 0 && self.height > 0,
+            "can't wrap around an empty grid"
+        );
+        (
+            r.rem_euclid(self.height as isize) as usize,
+            c.rem_euclid(self.width as isize) as usize,
+        )
+    }
+
+    /// `pos` moved by `(dr, dc)`, or `None` when that leaves the grid.
+    pub fn checked_offset(&self, (r, c): Pos, (dr, dc): (isize, isize)) -> Option<Pos> {
+        let pos = (r.checked_add_signed(dr)?, c.checked_add_signed(dc)?);
+        self.contains(pos).then_some(pos)
+    }
+
+    /// `pos` moved by `(dr, dc)`, wrapping around the edges.
+    pub fn wrapping_offset(&self, (r, c): Pos, (dr, dc): (isize, isize)) -> Pos {
+        self.wrap(r as isize + dr, c as isize + dc)
+    }
+
+    /// Up, down, left and right of `pos`, skipping those outside the grid.
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .filter_map(move |delta| self.checked_offset(pos, delta))
+    }
+
+    /// [`Grid::neighbors`] followed by `pos` itself.
+    pub fn neighbors_and_self(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbors(pos).chain(std::iter::once(pos))
+    }
+
+    /// All eight surrounding positions that are inside the grid.
+    pub fn neighbors_diagonal(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ALL.into_iter()
+            .filter_map(move |delta| self.checked_offset(pos, delta))
+    }
+
+    /// The four orthogonal neighbors on a torus: they always exist.
+    pub fn neighbors_wrapping(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL
+            .into_iter()
+            .map(move |delta| self.wrapping_offset(pos, delta))
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.cells[r * self.width..(r + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, r: usize) -> &mut [T] {
+        &mut self.cells[r * self.width..(r + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, c: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(c < self.width, "column {c} outside the grid");
+        self.cells.iter().skip(c).step_by(self.width)
+    }
+
+    /// Cells in row-major order.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    /// Every position, in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |r| (0..width).map(move |c| (r, c)))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Swap rows and columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|c| self.column(c).cloned())
+                .collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        assert!(self.contains(pos), "{pos:?} outside the grid");
+        &self.cells[self.flat_index(pos)]
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        assert!(self.contains(pos), "{pos:?} outside the grid");
+        let i = self.flat_index(pos);
+        &mut self.cells[i]
+    }
+}
+
+/// The raw bytes of each line.
+impl FromStr for Grid<u8> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_parse(s, |_, b| Ok(b))
+    }
+}
+
+/// One line per row, one character per cell.
+impl<T: Copy + Into<char>> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (r, row) in self.rows().enumerate() {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            for &cell in row {
+                write!(f, "{}", cell.into())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Grid {}x{}", self.width, self.height)?;
+        for row in self.rows() {
+            writeln!(f, "{row:?}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "abc\ndef\n";
+
+    #[test]
+    pub fn parse_test() {
+        let grid: Grid<u8> = SAMPLE.parse().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], b'f');
+        assert_eq!(grid.row(1), b"def");
+        assert_eq!(grid.to_string(), "abc\ndef");
+
+        let err = "abc\nde".parse::<Grid<u8>>().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = Grid::try_parse("12\n3x", |_, b| {
+            b.is_ascii_digit()
+                .then_some(b - b'0')
+                .ok_or(ErrorKind::Digit)
+        })
+        .unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected),
+            (2, 2, ErrorKind::Digit)
+        );
+    }
+
+    #[test]
+    pub fn access_test() {
+        let grid: Grid<u8> = SAMPLE.parse().unwrap();
+
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(*grid.get_wrapping(-1, -1), b'f');
+        assert_eq!(*grid.get_wrapping(2, 4), b'b');
+        assert_eq!(grid.checked_offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.wrapping_offset((0, 0), (-1, 0)), (1, 0));
+        assert!(grid.column(2).eq(b"cf"));
+    }
+
+    #[test]
+    #[should_panic = "can't wrap around an empty grid"]
+    pub fn wrap_test() {
+        Grid::new(0, 3, b'.').wrap(1, 1);
+    }
+
+    #[test]
+    #[should_panic = "column 3 outside the grid"]
+    pub fn column_test() {
+        let grid: Grid<u8> = SAMPLE.parse().unwrap();
+        grid.column(3).count();
+    }
+
+    #[test]
+    pub fn neighbors_test() {
+        let grid: Grid<u8> = SAMPLE.parse().unwrap();
+
+        assert_eq!(grid.neighbors((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors_and_self((0, 0)).count(), 3);
+        assert_eq!(grid.neighbors_diagonal((1, 1)).count(), 5);
+        assert_eq!(
+            grid.neighbors_wrapping((0, 0)).collect::<Vec<_>>(),
+            [(1, 0), (1, 0), (0, 2), (0, 1)]
+        );
+    }
+
+    #[test]
+    pub fn transpose_test() {
+        let grid: Grid<u8> = SAMPLE.parse().unwrap();
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(
+            Grid::from_rows(vec![b"ab".to_vec(), b"c".to_vec()], b' ').to_string(),
+            "ab\nc "
+        );
+    }
+}
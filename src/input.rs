@@ -0,0 +1,38 @@
+use std::{env, fmt, fs, io, path::PathBuf};
+
+/// Where a day's personal input lives, `input/2022/dayN.txt` in the `input` submodule.
+pub fn path(day: u8) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("input/2022/day{day}.txt"))
+}
+
+/// Read a day's input with the trailing newlines stripped.
+pub fn load(day: u8) -> io::Result<String> {
+    let mut input = fs::read_to_string(path(day))?;
+    input.truncate(input.trim_end_matches('\n').len());
+    Ok(input)
+}
+
+/// Report a regression test that can't run. Prints the reason, or panics when
+/// `AOC_REQUIRE_INPUTS` is set so a CI run with missing inputs fails loudly.
+pub fn skip(day: u8, reason: fmt::Arguments) {
+    if env::var_os("AOC_REQUIRE_INPUTS").is_some_and(|v| !v.is_empty()) {
+        panic!("day {day} regression: {reason} (AOC_REQUIRE_INPUTS is set)");
+    }
+    eprintln!("skipping day {day} regression: {reason}");
+}
+
+/// Like [`load`], but for regression tests: [`skip`]s the day and returns `None`
+/// when the input isn't checked out or is still an empty stub.
+pub fn load_or_skip(day: u8) -> Option<String> {
+    match load(day) {
+        Ok(input) if input.is_empty() => {
+            skip(day, format_args!("{} is empty", path(day).display()));
+            None
+        }
+        Ok(input) => Some(input),
+        Err(e) => {
+            skip(day, format_args!("{} ({e})", path(day).display()));
+            None
+        }
+    }
+}
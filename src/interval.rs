@@ -0,0 +1,242 @@
+//! Sets of integers stored as sorted, disjoint inclusive ranges.
+
+use std::{fmt, ops::RangeInclusive};
+
+/// Integer types an [`IntervalSet`] can hold.
+pub trait Bound: Copy + Ord + fmt::Debug {
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+    /// Number of values in `start..=end`, which needs more than 64 bits for
+    /// the whole `u64` or `i64` range.
+    fn count(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_bound {
+    ($($t:ty),*) => {
+        $(
+            impl Bound for $t {
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count(start: Self, end: Self) -> u128 {
+                    (end as i128 - start as i128) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_bound!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// A set of integers. Touching or overlapping ranges are merged, so two sets
+/// holding the same values compare equal.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sort and merge `ranges`, dropping the empty ones.
+    fn normalized(mut ranges: Vec<(T, T)>) -> Self {
+        ranges.retain(|(start, end)| start <= end);
+        ranges.sort_unstable();
+
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if last.1.succ().is_none_or(|next| start <= next) => {
+                    last.1 = last.1.max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        Self { ranges: merged }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range.into_inner());
+        *self = Self::normalized(ranges);
+    }
+
+    /// The disjoint ranges, in increasing order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| T::count(start, end))
+            .sum()
+    }
+
+    /// From the smallest to the largest value.
+    pub fn span(&self) -> Option<RangeInclusive<T>> {
+        Some(self.ranges.first()?.0..=self.ranges.last()?.1)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.contains_range(value..=value)
+    }
+
+    /// Whether every value of `range` is in the set.
+    pub fn contains_range(&self, range: RangeInclusive<T>) -> bool {
+        let (start, end) = range.into_inner();
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        start > end
+            || self
+                .ranges
+                .get(i)
+                .is_some_and(|&(s, e)| s <= start && end <= e)
+    }
+
+    /// Whether any value of `range` is in the set.
+    pub fn overlaps(&self, range: RangeInclusive<T>) -> bool {
+        let (start, end) = range.into_inner();
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        start <= end && self.ranges.get(i).is_some_and(|&(s, _)| s <= end)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalized([&self.ranges[..], &other.ranges[..]].concat())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        while let (Some(&&(s1, e1)), Some(&&(s2, e2))) = (a.peek(), b.peek()) {
+            let (start, end) = (s1.max(s2), e1.min(e2));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if e1 < e2 {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        match self.span() {
+            Some(span) => self.intersection(&other.gaps(span)),
+            None => Self::new(),
+        }
+    }
+
+    /// The values of `within` that are not in the set.
+    pub fn gaps(&self, within: RangeInclusive<T>) -> Self {
+        let (lo, hi) = within.into_inner();
+        let mut ranges = Vec::new();
+        let mut next = Some(lo);
+
+        for &(start, end) in &self.ranges {
+            let Some(from) = next.filter(|&from| from <= hi) else {
+                break;
+            };
+            if end < from {
+                continue;
+            }
+            if from < start {
+                ranges.push((from, start.pred().unwrap().min(hi)));
+            }
+            next = end.succ();
+        }
+        if let Some(from) = next.filter(|&from| from <= hi) {
+            ranges.push((from, hi));
+        }
+
+        Self { ranges }
+    }
+}
+
+impl<T: Bound> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        Self::normalized(iter.into_iter().map(RangeInclusive::into_inner).collect())
+    }
+}
+
+impl<T: Bound> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Self::normalized(vec![range.into_inner()])
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.ranges.iter().map(|(start, end)| start..=end))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[RangeInclusive<i32>]) -> IntervalSet<i32> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    pub fn normalize_test() {
+        let s = set(&[5..=7, 1..=2, 3..=3, 6..=9, RangeInclusive::new(12, 11)]);
+
+        assert_eq!(s.ranges().collect::<Vec<_>>(), [1..=3, 5..=9]);
+        assert_eq!(s.len(), 8);
+        assert_eq!(s.span(), Some(1..=9));
+        assert!(s.contains(2) && !s.contains(4));
+        assert!(s.contains_range(6..=9) && !s.contains_range(3..=5));
+        assert!(s.overlaps(3..=5) && !s.overlaps(4..=4));
+
+        let mut full = IntervalSet::from(u8::MAX - 1..=u8::MAX);
+        full.insert(0..=u8::MAX - 2);
+        assert_eq!(full.len(), 256);
+
+        assert_eq!(IntervalSet::from(0..=u64::MAX).len(), 1 << 64);
+        assert_eq!(IntervalSet::from(i64::MIN..=i64::MAX).len(), 1 << 64);
+        assert_eq!(IntervalSet::from(i64::MIN..=-1).len(), 1 << 63);
+    }
+
+    #[test]
+    pub fn operations_test() {
+        let a = set(&[1..=5, 10..=15]);
+        let b = set(&[4..=11, 20..=20]);
+
+        assert_eq!(a.union(&b), set(&[1..=15, 20..=20]));
+        assert_eq!(a.intersection(&b), set(&[4..=5, 10..=11]));
+        assert_eq!(a.difference(&b), set(&[1..=3, 12..=15]));
+        assert_eq!(b.difference(&a), set(&[6..=9, 20..=20]));
+        assert_eq!(a.gaps(0..=20), set(&[0..=0, 6..=9, 16..=20]));
+        assert_eq!(a.gaps(3..=12), set(&[6..=9]));
+        assert!(IntervalSet::<i32>::new().difference(&a).is_empty());
+    }
+}
@@ -1,5 +1,11 @@
 use aoc_runner_derive::aoc_lib;
+pub mod answers;
 pub mod common;
+pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod parse;
+pub mod solver;
 
 pub mod day01;
 pub mod day02;
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use nom::{AsBytes, Input, Offset, Parser, error::ErrorKind};
+
+/// Where and why a generator rejected its input.
+///
+/// `line` and `column` are 1-based, `column` counts bytes, and `snippet` is the
+/// whole offending line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+    pub expected: ErrorKind,
+}
+
+impl ParseError {
+    /// Build an error for `remaining`, which must be a suffix of `input`.
+    pub fn new<I: AsBytes + Offset>(input: &I, remaining: &I, expected: ErrorKind) -> Self {
+        let offset = input.offset(remaining);
+        let bytes = input.as_bytes();
+        let line_start = bytes[..offset]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |p| p + 1);
+        let line_end = bytes[offset..]
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+            .map_or(bytes.len(), |p| offset + p);
+
+        Self {
+            line: bytes[..line_start].iter().filter(|&&b| b == b'\n').count() + 1,
+            column: offset - line_start + 1,
+            snippet: String::from_utf8_lossy(&bytes[line_start..line_end]).into_owned(),
+            expected,
+        }
+    }
+
+    /// Shift the reported line, for errors found in a later section of the input.
+    pub fn with_line_offset(mut self, lines: usize) -> Self {
+        self.line += lines;
+        self
+    }
+}
+
+fn describe(kind: ErrorKind) -> &'static str {
+    match kind {
+        ErrorKind::Tag => "literal text",
+        ErrorKind::Char => "a specific character",
+        ErrorKind::OneOf => "one of the allowed characters",
+        ErrorKind::Digit => "digits",
+        ErrorKind::Alpha => "letters",
+        ErrorKind::MapRes => "a number that fits",
+        ErrorKind::TakeWhile1 => "at least one valid character",
+        ErrorKind::Eof => "end of input",
+        ErrorKind::Complete => "more input",
+        ErrorKind::Verify => "a valid value",
+        _ => "a valid token",
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "line {}, column {}: expected {} ({:?})",
+            self.line,
+            self.column,
+            describe(self.expected),
+            self.expected
+        )?;
+        writeln!(f, "  {}", self.snippet)?;
+        write!(f, "  {:>width$}", "^", width = self.column)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn finish<I, O>(input: &I, result: nom::IResult<I, O>) -> Result<O, ParseError>
+where
+    I: Input + AsBytes + Offset,
+{
+    match result {
+        Ok((rest, output)) => {
+            // Trailing newlines are fine, anything else means the parser stopped early
+            match rest
+                .as_bytes()
+                .iter()
+                .position(|b| !b.is_ascii_whitespace())
+            {
+                None => Ok(output),
+                Some(p) => Err(ParseError::new(input, &rest.take_from(p), ErrorKind::Eof)),
+            }
+        }
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+            Err(ParseError::new(input, &e.input, e.code))
+        }
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new(
+            input,
+            &input.take_from(input.input_len()),
+            ErrorKind::Complete,
+        )),
+    }
+}
+
+/// Fallible counterpart of `common::nom::process_input`: the parser must consume
+/// everything but trailing whitespace.
+pub fn try_process_input<I, O, F>(mut f: F) -> impl FnMut(I) -> Result<O, ParseError>
+where
+    I: Input + AsBytes + Offset,
+    F: Parser<I, Output = O, Error = nom::error::Error<I>>,
+{
+    move |input: I| {
+        let result = f.parse(input.clone());
+        finish(&input, result)
+    }
+}
+
+/// Run `f` over every non-empty line, so a bad line is reported with the
+/// parser's own error rather than as unconsumed input.
+pub fn try_process_lines<I, O, F>(mut f: F) -> impl FnMut(I) -> Result<Vec<O>, ParseError>
+where
+    I: Input + AsBytes + Offset,
+    F: Parser<I, Output = O, Error = nom::error::Error<I>>,
+{
+    move |input: I| {
+        let bytes = input.as_bytes();
+        let mut res = Vec::new();
+        let mut start = 0;
+
+        while start < bytes.len() {
+            let len = bytes[start..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .unwrap_or(bytes.len() - start);
+            let line_len = len - usize::from(bytes[start..start + len].ends_with(b"\r"));
+
+            if line_len > 0 {
+                let line = input.take_from(start).take(line_len);
+                let result = f.parse(line.clone());
+                res.push(finish(&input, result)?);
+            }
+
+            start += len + 1;
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{bytes::complete::tag, character::complete::digit1, combinator::map};
+
+    use super::*;
+
+    #[test]
+    pub fn location_test() {
+        let input = "12\n34\nab5\n";
+        let err = ParseError::new(&input, &&input[7..], ErrorKind::Digit);
+
+        assert_eq!(err.line, 3);
+        assert_eq!(err.column, 2);
+        assert_eq!(err.snippet, "ab5");
+        assert_eq!(err.to_string().lines().nth(2), Some("   ^"));
+    }
+
+    #[test]
+    pub fn lines_test() {
+        let mut parse = try_process_lines(map((digit1, tag("-"), digit1), |(a, _, b)| (a, b)));
+
+        assert_eq!(parse("1-2\n3-4\n"), Ok(vec![("1", "2"), ("3", "4")]));
+
+        let err = parse("1-2\n3+4").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+        assert_eq!(err.expected, ErrorKind::Tag);
+        assert_eq!(err.snippet, "3+4");
+
+        let err = parse("1-2\n3-4x").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(err.expected, ErrorKind::Eof);
+    }
+
+    #[test]
+    pub fn input_test() {
+        let mut parse = try_process_input(digit1::<&[u8], _>);
+
+        assert_eq!(parse(b"123\n"), Ok(&b"123"[..]));
+        assert_eq!(parse(b"x").unwrap_err().expected, ErrorKind::Digit);
+    }
+}
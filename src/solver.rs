@@ -0,0 +1,314 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::*;
+
+/// A puzzle answer, either numeric or text (crate stacks, CRT letters, SNAFU).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i128),
+    Str(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{n}"),
+            Answer::Str(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Int(n.into())
+            }
+        })*
+    };
+}
+
+answer_from_int!(u16, u32, u64, i64);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Int(n as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+/// A day's solution with its parsed input type, e.g. `Day::<12>::parse(input)`.
+pub trait Solver {
+    type Parsed;
+
+    /// Whether [`Solver::part2`] returns an answer, without having to run it.
+    const HAS_PART2: bool = false;
+
+    /// Bad input is reported as an error instead of a panic.
+    fn try_parse(input: &str) -> Result<Self::Parsed, String>;
+
+    fn parse(input: &str) -> Self::Parsed {
+        Self::try_parse(input).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn part1(parsed: &Self::Parsed) -> Answer;
+
+    /// `None` for days that only have one puzzle (day 25).
+    fn part2(_parsed: &Self::Parsed) -> Option<Answer> {
+        None
+    }
+}
+
+/// Marker type that [`Solver`] is implemented on for every day.
+pub struct Day<const N: u8>;
+
+macro_rules! try_parse {
+    (try, $day:ident, $input:expr) => {
+        $day::try_generator($input).map_err(|e| e.to_string())
+    };
+    (try_bytes, $day:ident, $input:expr) => {
+        $day::try_generator($input.as_bytes()).map_err(|e| e.to_string())
+    };
+    (str, $day:ident, $input:expr) => {
+        Ok($day::generator($input))
+    };
+    (raw, $day:ident, $input:expr) => {
+        Ok($input.as_bytes().to_vec())
+    };
+}
+
+macro_rules! solvers {
+    ($($num:literal => $day:ident $kind:ident $parsed:ty, [part1 $(, $part2:ident)?];)*) => {
+        $(impl Solver for Day<$num> {
+            type Parsed = $parsed;
+
+            fn try_parse(input: &str) -> Result<Self::Parsed, String> {
+                try_parse!($kind, $day, input)
+            }
+
+            fn part1(parsed: &Self::Parsed) -> Answer {
+                $day::part1(parsed).into()
+            }
+
+            $(const HAS_PART2: bool = true;
+
+            fn part2(parsed: &Self::Parsed) -> Option<Answer> {
+                Some($day::$part2(parsed).into())
+            })?
+        })*
+    };
+}
+
+solvers! {
+    1 => day01 try Vec<u32>, [part1, part2];
+    2 => day02 try_bytes Vec<(usize, usize)>, [part1, part2];
+    3 => day03 try Vec<String>, [part1, part2];
+    4 => day04 try Vec<day04::Assignments>, [part1, part2];
+    5 => day05 try day05::Input, [part1, part2];
+    6 => day06 raw Vec<u8>, [part1, part2];
+    7 => day07 try day07::FsNode, [part1, part2];
+    8 => day08 try grid::Grid<u8>, [part1, part2];
+    9 => day09 try Vec<day09::Move>, [part1, part2];
+    10 => day10 try Vec<day10::Instructions>, [part1, part2];
+    11 => day11 try Vec<day11::Monkey>, [part1, part2];
+    12 => day12 try day12::HeightMap, [part1, part2];
+    13 => day13 try Vec<[day13::Signal; 2]>, [part1, part2];
+    14 => day14 try grid::Grid<u8>, [part1, part2];
+    15 => day15 try Vec<day15::SensorReport>, [part1, part2];
+    16 => day16 try day16::Graph, [part1, part2];
+    17 => day17 raw Vec<u8>, [part1, part2];
+    18 => day18 try ahash::HashSet<day18::Cube>, [part1, part2];
+    19 => day19 try Vec<day19::BluePrint>, [part1, part2];
+    20 => day20 try Vec<day20::Node>, [part1, part2];
+    21 => day21 try ahash::HashMap<String, day21::Operation>, [part1, part2];
+    22 => day22 try day22::Input, [part1, part2];
+    23 => day23 try (grid::Grid<u8>, Vec<day23::Elf>), [part1, part2];
+    24 => day24 try day24::World, [part1, part2];
+    25 => day25 try i64, [part1];
+    // Insert solver before
+}
+
+/// A type-erased [`Solver`], as listed by [`registry`].
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub day: u8,
+    pub title: &'static str,
+    solve: fn(&str) -> (Answer, Option<Answer>),
+    part1: fn(&str) -> Answer,
+    part2: fn(&str) -> Option<Answer>,
+    run: fn(&str, Option<u8>) -> Result<Run, String>,
+}
+
+/// What [`Entry::run`] computed, and how long each step took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub generator: Duration,
+    /// `(part, answer, runner time)` for every part that ran.
+    pub parts: Vec<(u8, Answer, Duration)>,
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
+
+impl fmt::Debug for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {}: {}", self.day, self.title)
+    }
+}
+
+impl Entry {
+    const fn new<S: Solver>(day: u8, title: &'static str) -> Self {
+        Self {
+            day,
+            title,
+            solve: |input| {
+                let parsed = S::parse(input);
+                (S::part1(&parsed), S::part2(&parsed))
+            },
+            part1: |input| S::part1(&S::parse(input)),
+            part2: |input| S::part2(&S::parse(input)),
+            run: |input, part| {
+                let (parsed, generator) = timed(|| S::try_parse(input));
+                let parsed = parsed?;
+                let mut parts = Vec::new();
+
+                if part.is_none_or(|p| p == 1) {
+                    let (answer, runner) = timed(|| S::part1(&parsed));
+                    parts.push((1, answer, runner));
+                }
+                if part.is_none_or(|p| p == 2)
+                    && let (Some(answer), runner) = timed(|| S::part2(&parsed))
+                {
+                    parts.push((2, answer, runner));
+                }
+
+                Ok(Run { generator, parts })
+            },
+        }
+    }
+
+    /// Parse once and run both parts.
+    pub fn solve(&self, input: &str) -> (Answer, Option<Answer>) {
+        (self.solve)(input)
+    }
+
+    pub fn part1(&self, input: &str) -> Answer {
+        (self.part1)(input)
+    }
+
+    pub fn part2(&self, input: &str) -> Option<Answer> {
+        (self.part2)(input)
+    }
+
+    /// Parse and run `part`, or both parts when `None`, timing each step.
+    /// Unlike the other methods, input the day's generator rejects is an error
+    /// instead of a panic. The parts themselves may still panic.
+    pub fn run(&self, input: &str, part: Option<u8>) -> Result<Run, String> {
+        (self.run)(input, part)
+    }
+}
+
+const REGISTRY: &[Entry] = &[
+    Entry::new::<Day<1>>(1, "Calorie Counting"),
+    Entry::new::<Day<2>>(2, "Rock Paper Scissors"),
+    Entry::new::<Day<3>>(3, "Rucksack Reorganization"),
+    Entry::new::<Day<4>>(4, "Camp Cleanup"),
+    Entry::new::<Day<5>>(5, "Supply Stacks"),
+    Entry::new::<Day<6>>(6, "Tuning Trouble"),
+    Entry::new::<Day<7>>(7, "No Space Left On Device"),
+    Entry::new::<Day<8>>(8, "Treetop Tree House"),
+    Entry::new::<Day<9>>(9, "Rope Bridge"),
+    Entry::new::<Day<10>>(10, "Cathode-Ray Tube"),
+    Entry::new::<Day<11>>(11, "Monkey in the Middle"),
+    Entry::new::<Day<12>>(12, "Hill Climbing Algorithm"),
+    Entry::new::<Day<13>>(13, "Distress Signal"),
+    Entry::new::<Day<14>>(14, "Regolith Reservoir"),
+    Entry::new::<Day<15>>(15, "Beacon Exclusion Zone"),
+    Entry::new::<Day<16>>(16, "Proboscidea Volcanium"),
+    Entry::new::<Day<17>>(17, "Pyroclastic Flow"),
+    Entry::new::<Day<18>>(18, "Boiling Boulders"),
+    Entry::new::<Day<19>>(19, "Not Enough Minerals"),
+    Entry::new::<Day<20>>(20, "Grove Positioning System"),
+    Entry::new::<Day<21>>(21, "Monkey Math"),
+    Entry::new::<Day<22>>(22, "Monkey Map"),
+    Entry::new::<Day<23>>(23, "Unstable Diffusion"),
+    Entry::new::<Day<24>>(24, "Blizzard Basin"),
+    Entry::new::<Day<25>>(25, "Full of Hot Air"),
+    // Insert entry before
+];
+
+/// Every day, in order.
+pub fn registry() -> &'static [Entry] {
+    REGISTRY
+}
+
+/// Look up a day by its number (1-based).
+pub fn get(day: u8) -> Option<&'static Entry> {
+    REGISTRY.iter().find(|entry| entry.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn registry_test() {
+        assert!(registry().iter().zip(1..).all(|(e, day)| e.day == day));
+        assert!(get(0).is_none());
+        assert!(get(26).is_none());
+    }
+
+    #[test]
+    pub fn solve_test() {
+        let entry = get(1).unwrap();
+        let input = "1000\n2000\n\n4000\n\n5000\n6000";
+
+        assert_eq!(
+            entry.solve(input),
+            (Answer::Int(11000), Some(Answer::Int(18000)))
+        );
+        assert_eq!(entry.part1(input).to_string(), "11000");
+    }
+
+    #[test]
+    pub fn run_test() {
+        let run = get(25).unwrap().run("1=\n2", None).unwrap();
+        assert_eq!(run.parts.len(), 1);
+        assert_eq!((run.parts[0].0, &run.parts[0].1), (1, &Answer::from("10")));
+
+        let run = get(1).unwrap().run("1000\n\n2000", Some(2)).unwrap();
+        assert_eq!((run.parts[0].0, &run.parts[0].1), (2, &Answer::Int(3000)));
+
+        assert!(get(1).unwrap().run("1000\nx", None).is_err());
+        assert!(get(22).unwrap().run("..#\n10R5", None).is_err());
+        assert!(get(22).unwrap().run("..x\n\n10R5", None).is_err());
+        assert!(get(22).unwrap().run("..#\n\n10X5", None).is_err());
+    }
+
+    #[test]
+    pub fn typed_test() {
+        let parsed = Day::<25>::parse("1=\n2");
+
+        assert_eq!(parsed, 5);
+        assert_eq!(Day::<25>::part1(&parsed), Answer::from("10"));
+        assert_eq!(Day::<25>::part2(&parsed), None);
+        const { assert!(!Day::<25>::HAS_PART2 && Day::<24>::HAS_PART2) };
+    }
+}
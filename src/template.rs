@@ -18,12 +18,12 @@ pub fn part2(inputs: &Object) -> usize {
     unimplemented!()
 }
 
+pub const SAMPLE: &str = r"..##.......";
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const SAMPLE: &str = r"..##.......";
-
     #[test]
     pub fn input_test() {
         println!("{:?}", generator(SAMPLE));
@@ -42,18 +42,9 @@ mod tests {
     }
 
     mod regression {
-        use super::*;
-
-        const INPUT: &str = include_str!("../input/2022/dayN.txt");
-        const ANSWERS: (usize, usize) = (0, 0);
-
         #[test]
         pub fn test() {
-            let input = INPUT.trim_end_matches('\n');
-            // let output = generator(input);
-
-            // assert_eq!(part1(&output), ANSWERS.0);
-            // assert_eq!(part2(&output), ANSWERS.1);
+            crate::answers::check(N);
         }
     }
 }